@@ -1,7 +1,6 @@
-use std::collections::VecDeque;
-use std::{collections::HashSet, fs::read_to_string};
+use std::fs::read_to_string;
 
-use advent_of_code_2024::{Dir, Grid, Loc};
+use advent_of_code_2024::{count_paths, Dir, Grid, Loc, PathCount};
 
 fn read_input() -> Grid<u32> {
     Grid(
@@ -19,84 +18,34 @@ fn read_input() -> Grid<u32> {
     )
 }
 
-fn count_trails(grid: &Grid<u32>, start_pos: Loc) -> u32 {
-    let dirs = [Dir::E, Dir::S, Dir::W, Dir::N];
-
-    let mut queue: VecDeque<(Loc, u32)> = VecDeque::new();
-    queue.push_back((start_pos, 0));
-
-    let mut trail_ends = HashSet::new();
-    while !queue.is_empty() {
-        let (loc, height) = queue.pop_front().unwrap();
-        if height == 9 {
-            trail_ends.insert(loc);
-            continue;
-        }
-
-        for dir in dirs {
-            if let Some(next_pos) = grid.step(&loc, dir) {
-                if grid[&next_pos] == height + 1 {
-                    queue.push_back((next_pos, height + 1));
-                }
-            }
-        }
-    }
-
-    trail_ends.len() as u32
+fn trail_steps<'a>(grid: &'a Grid<u32>, loc: &Loc) -> impl Iterator<Item = Loc> + 'a {
+    let loc = *loc;
+    let height = grid[&loc];
+    [Dir::E, Dir::S, Dir::W, Dir::N]
+        .into_iter()
+        .filter_map(move |dir| grid.step(&loc, dir))
+        .filter(move |next_pos| grid[next_pos] == height + 1)
 }
 
-fn part1() -> u32 {
-    let grid = read_input();
-    grid.iter_with_loc()
-        .filter_map(|(pos, height)| {
-            if *height == 0 {
-                Some(count_trails(&grid, pos))
-            } else {
-                None
-            }
-        })
-        .sum()
+fn count_trails(grid: &Grid<u32>) -> Vec<PathCount<Loc>> {
+    let trailheads = grid
+        .iter_with_loc()
+        .filter_map(|(pos, height)| if *height == 0 { Some(pos) } else { None });
+    count_paths(
+        trailheads,
+        |loc| trail_steps(grid, loc),
+        |loc| grid[loc] == 9,
+    )
 }
 
-
-fn count_trails_distinct(grid: &Grid<u32>, start_pos: Loc) -> u32 {
-    let dirs = [Dir::E, Dir::S, Dir::W, Dir::N];
-
-    let mut queue: VecDeque<(Loc, u32)> = VecDeque::new();
-    queue.push_back((start_pos, 0));
-
-    let mut num_trails = 0;
-    while !queue.is_empty() {
-        let (loc, height) = queue.pop_front().unwrap();
-        if height == 9 {
-            num_trails += 1;
-            continue;
-        }
-
-        for dir in dirs {
-            if let Some(next_pos) = grid.step(&loc, dir) {
-                if grid[&next_pos] == height + 1 {
-                    queue.push_back((next_pos, height + 1));
-                }
-            }
-        }
-    }
-
-    num_trails
+fn part1() -> usize {
+    let grid = read_input();
+    count_trails(&grid).iter().map(|t| t.sinks.len()).sum()
 }
 
-
-fn part2() -> u32 {
+fn part2() -> u128 {
     let grid = read_input();
-    grid.iter_with_loc()
-        .filter_map(|(pos, height)| {
-            if *height == 0 {
-                Some(count_trails_distinct(&grid, pos))
-            } else {
-                None
-            }
-        })
-        .sum()
+    count_trails(&grid).iter().map(|t| t.paths).sum()
 }
 
 fn main() {
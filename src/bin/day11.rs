@@ -1,3 +1,4 @@
+use advent_of_code_2024::math::Digits;
 use std::{collections::HashMap, fs};
 
 fn read_input() -> Vec<u64> {
@@ -8,37 +9,14 @@ fn read_input() -> Vec<u64> {
         .collect()
 }
 
-const POWERS_OF_10: [u64; 11] = [
-    1,
-    10,
-    100,
-    1000,
-    10_000,
-    100_000,
-    1_000_000,
-    10_000_000,
-    100_000_000,
-    1_000_000_000,
-    10_000_000,
-];
-
-fn count_digits(mut n: u64) -> usize {
-    let mut count = 0;
-    while n > 0 {
-        n /= 10;
-        count += 1;
-    }
-    count
-}
-
 fn successor(n: u64) -> Vec<u64> {
     if n == 0 {
         return vec![1];
     }
-    let digits = count_digits(n);
-    if digits % 2 == 0 {
-        let splitmod = POWERS_OF_10[digits / 2];
-        vec![n / splitmod, n % splitmod]
+    let digits = n.digit_count();
+    if digits.is_multiple_of(2) {
+        let (high, low) = n.split_digits(digits / 2);
+        vec![high, low]
     } else {
         vec![2024 * n]
     }
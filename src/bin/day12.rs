@@ -1,7 +1,7 @@
 use itertools::Itertools;
 use std::{collections::VecDeque, fs::read_to_string};
 
-use advent_of_code_2024::{Dir, Grid, Loc};
+use advent_of_code_2024::{BitGrid, Dir, Grid, Loc};
 
 fn read_input() -> Grid<char> {
     Grid::from_string(&read_to_string("input/day12.txt").unwrap())
@@ -12,27 +12,25 @@ struct GardenRegion {
     perimeter: u64,
 }
 
-fn bfs(start: Loc, grid: &Grid<char>, visited: &mut Grid<bool>) -> GardenRegion {
+fn bfs(start: Loc, grid: &Grid<char>, visited: &mut BitGrid) -> GardenRegion {
     let mut queue = VecDeque::new();
     let mut area = 0;
     let mut perimeter = 0;
     let ch = grid[&start];
 
     queue.push_back(start);
-    visited[&start] = true;
+    visited.insert(&start);
 
     while let Some(l) = queue.pop_front() {
         area += 1;
-        for dir in [Dir::N, Dir::E, Dir::S, Dir::W] {
+        for dir in Dir::CARDINAL {
             match grid.step(&l, dir) {
                 None => perimeter += 1, // we have reached the edge of the grid
                 Some(next) => {
                     if grid[&next] == ch {
-                        if visited[&next] {
-                            continue;
+                        if visited.insert(&next) {
+                            queue.push_back(next);
                         }
-                        queue.push_back(next);
-                        visited[&next] = true;
                     } else {
                         perimeter += 1;
                     }
@@ -46,14 +44,14 @@ fn bfs(start: Loc, grid: &Grid<char>, visited: &mut Grid<bool>) -> GardenRegion
 
 fn part1() -> u64 {
     let grid = read_input();
-    let mut visited = Grid::new(grid.n_rows(), grid.n_cols(), false);
+    let mut visited = BitGrid::new(grid.n_rows(), grid.n_cols());
 
     let mut score = 0u64;
 
     for r in 0..grid.n_rows() {
         for c in 0..grid.n_cols() {
-            if !visited[&(r, c)] {
-                let region = bfs((r, c), &grid, &mut visited);
+            if !visited[&Loc(r, c)] {
+                let region = bfs(Loc(r, c), &grid, &mut visited);
                 score += region.area * region.perimeter;
             }
         }
@@ -107,25 +105,24 @@ fn interior_corners(loc: Loc, grid: &Grid<char>) -> u64 {
     corners
 }
 
-fn bfs2(start: Loc, grid: &Grid<char>, visited: &mut Grid<bool>) -> u64 {
+fn bfs2(start: Loc, grid: &Grid<char>, visited: &mut BitGrid) -> u64 {
     let mut stack = Vec::new();
 
     stack.push(start);
     let mut area = 0;
     let mut num_sides = 0;
-    visited[&start] = true;
+    visited.insert(&start);
 
     while let Some(l) = stack.pop() {
         area += 1;
         num_sides += exterior_corners(l, grid);
         num_sides += interior_corners(l, grid);
 
-        for dir in [Dir::N, Dir::E, Dir::S, Dir::W] {
+        for dir in Dir::CARDINAL {
             if is_same_in_direction(grid, l, dir) {
                 let next = grid.step(&l, dir).unwrap();
-                if !visited[&next] {
-                stack.push(next);
-                visited[&next] = true;
+                if visited.insert(&next) {
+                    stack.push(next);
                 }
             }
         }
@@ -136,14 +133,14 @@ fn bfs2(start: Loc, grid: &Grid<char>, visited: &mut Grid<bool>) -> u64 {
 
 fn part2() -> u64 {
     let grid = read_input();
-    let mut visited = Grid::new(grid.n_rows(), grid.n_cols(), false);
+    let mut visited = BitGrid::new(grid.n_rows(), grid.n_cols());
 
     let mut score = 0u64;
 
     for r in 0..grid.n_rows() {
         for c in 0..grid.n_cols() {
-            if !visited[&(r, c)] {
-                score += bfs2((r, c), &grid, &mut visited);
+            if !visited[&Loc(r, c)] {
+                score += bfs2(Loc(r, c), &grid, &mut visited);
             }
         }
     }
@@ -1,3 +1,4 @@
+use advent_of_code_2024::linalg;
 use itertools::Itertools;
 use regex::Regex;
 use std::fs::read_to_string;
@@ -72,34 +73,12 @@ fn read_input() -> Vec<Machine> {
         .collect()
 }
 
-// Approach: use cramer's rule to solve the 2x2 linear equation
-
-fn determinant(col1: Vector2, col2: Vector2) -> i64 {
-    col1.0 * col2.1 - col2.0 * col1.1
-}
-
-fn test_soln(row1: Vector2, row2: Vector2, row_weights: Vector2, target: Vector2) -> bool {
-    row1.0 * row_weights.0 + row2.0 * row_weights.1 == target.0
-        && row1.1 * row_weights.0 + row2.1 * row_weights.1 == target.1
-}
-
+// Approach: solve the 2x2 linear system exactly, A and B presses cost 3 and 1 tokens
 fn find_soln(m: &Machine) -> Option<i64> {
-    let det = determinant(m.a, m.b);
-    // special case: they are the same line
-    if det == 0 {
-        println!("Encountered 0 determinant");
-        return None;
-    }
-
-    let row_weights = (
-        determinant(m.prize, m.b) / det,
-        determinant(m.a, m.prize) / det,
-    );
-    if test_soln(m.a, m.b, row_weights, m.prize) {
-        Some(3 * row_weights.0 + row_weights.1)
-    } else {
-        None
-    }
+    let buttons = vec![vec![m.a.0, m.b.0], vec![m.a.1, m.b.1]];
+    let presses = linalg::min_cost_solution(&buttons, &[m.prize.0, m.prize.1], &[3, 1])
+        .expect("Could not solve claw machine")?;
+    Some((3 * presses[0] + presses[1]) as i64)
 }
 
 fn part1() -> i64 {
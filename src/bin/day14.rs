@@ -1,4 +1,4 @@
-use advent_of_code_2024::{Grid, Vec2};
+use advent_of_code_2024::{Grid, Midlines, Point, Rect, Vec2};
 use std::fs::read_to_string;
 
 type Num = i32;
@@ -49,22 +49,17 @@ fn part1() -> i32 {
     let robots = read_input();
     let robots = robots.into_iter().map(|r| {
         let mut robot = r.step(100);
-        robot.position.x = robot.position.x.rem_euclid(101);
-        robot.position.y = robot.position.y.rem_euclid(103);
+        robot.position = robot.position.rem_euclid(Vec2::new(101, 103));
         robot
     });
 
+    let quadrants = Rect::with_size(103, 101).quadrants(Midlines::Exclude);
     let quadrant_counts = robots.fold([0, 0, 0, 0], |mut acc, r| {
-        // ignore the middle row & col
-        if r.position.x == 50 || r.position.y == 51 {
-            return acc;
+        let cell = Point::from(r.position);
+        // robots on the middle row & col are in no quadrant
+        if let Some(idx) = quadrants.iter().position(|q| q.contains(&cell)) {
+            acc[idx] += 1;
         }
-
-        let top = (r.position.y < 51) as usize;
-        let left = (r.position.x < 50) as usize;
-        let idx: usize = top << 1 | left;
-
-        acc[idx] += 1;
         acc
     });
 
@@ -82,19 +77,7 @@ fn part2() -> i32 {
         shape.set_all('.');
         robots.iter_mut().for_each(|robot| {
             robot.step_inplace(1);
-            let c = robot
-                .position
-                .x
-                .rem_euclid(101)
-                .try_into()
-                .expect("Invalid index");
-            let r = robot
-                .position
-                .y
-                .rem_euclid(103)
-                .try_into()
-                .expect("Invalid index");
-            shape[&(r, c)] = '*';
+            shape[robot.position.rem_euclid(Vec2::new(101, 103))] = '*';
         });
 
         let has_tree = shape
@@ -49,13 +49,7 @@ fn part1() -> u32 {
     let mut robot_pos = grid.find_item(&ROBOT).expect("No robot found in input");
 
     for mv in moves {
-        let dir = match mv {
-            '^' => Dir::N,
-            '>' => Dir::E,
-            '<' => Dir::W,
-            'v' => Dir::S,
-            _ => unreachable!(),
-        };
+        let dir = Dir::try_from(mv).expect("Invalid move in input");
         let did_move = try_move(&mut grid, robot_pos, dir);
         if did_move {
             robot_pos = grid.step(&robot_pos, dir).unwrap();
@@ -64,7 +58,7 @@ fn part1() -> u32 {
 
     let s: usize = grid
         .iter_with_loc()
-        .map(|((r, c), ch)| if *ch == BOX { 100 * r + c } else { 0 })
+        .map(|(Loc(r, c), ch)| if *ch == BOX { 100 * r + c } else { 0 })
         .sum();
     s as u32
 }
@@ -165,13 +159,7 @@ fn part2() -> usize {
     let mut robot_pos = grid.find_item(&ROBOT).expect("No robot found in input");
 
     for mv in moves {
-        let dir = match mv {
-            '^' => Dir::N,
-            '>' => Dir::E,
-            '<' => Dir::W,
-            'v' => Dir::S,
-            _ => unreachable!(),
-        };
+        let dir = Dir::try_from(mv).expect("Invalid move in input");
         let did_move = try_move2(&mut grid, robot_pos, dir);
         if did_move {
             robot_pos = grid.step(&robot_pos, dir).unwrap();
@@ -179,7 +167,7 @@ fn part2() -> usize {
     }
 
     grid.iter_with_loc()
-        .map(|((r, c), ch)| if *ch == BOX_LEFT { 100 * r + c } else { 0 })
+        .map(|(Loc(r, c), ch)| if *ch == BOX_LEFT { 100 * r + c } else { 0 })
         .sum()
 }
 
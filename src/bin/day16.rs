@@ -1,7 +1,7 @@
 use std::collections::{BinaryHeap, HashMap, HashSet};
 use std::fs::read_to_string;
 
-use advent_of_code_2024::{Dir, Grid, Loc};
+use advent_of_code_2024::{Dir, Grid, Loc, Turn};
 
 fn read_input() -> Grid<char> {
     let path = "input/day16.txt";
@@ -47,17 +47,16 @@ fn dijkstra(grid: &Grid<char>, start: Loc, end: Loc) -> Option<u32> {
         visited.insert(curr.position);
 
         // add turns
-        for next_dir in [
-            curr.heading.rotate_clockwise(),
-            curr.heading.rotate_counterclockwise(),
-        ] {
+        for next_dir in [Turn::Right, Turn::Left].map(|turn| curr.heading.apply(turn)) {
             if let Some(next_pos) = grid.step(&curr.position, next_dir) {
                 if visited.contains(&next_pos) {
                     continue;
                 }
                 if grid[&next_pos] != '#' {
+                    let turns = Dir::turns_between(curr.heading, next_dir)
+                        .expect("Left and right are quarter turns");
                     pq.push(State {
-                        cost: curr.cost + 1001,
+                        cost: curr.cost + 1000 * turns + 1,
                         position: next_pos,
                         heading: next_dir,
                     });
@@ -158,11 +157,10 @@ fn dijkstra2(
         }
 
         // add turns
-        for next_dir in [
-            curr.heading.rotate_clockwise(),
-            curr.heading.rotate_counterclockwise(),
-        ] {
-            let next_cost = curr.cost + 1000;
+        for next_dir in [Turn::Right, Turn::Left].map(|turn| curr.heading.apply(turn)) {
+            let turns = Dir::turns_between(curr.heading, next_dir)
+                .expect("Left and right are quarter turns");
+            let next_cost = curr.cost + 1000 * turns;
             let is_valid_cost = parents
                 .entry((curr.position, next_dir))
                 .or_default()
@@ -223,7 +221,7 @@ fn part2(min_path_cost: u32) -> usize {
     let end = grid.find_item(&'E').unwrap();
     let parents = dijkstra2(&grid, start, end, min_path_cost);
     let mut shortest_path_items = HashSet::new();
-    for dir in [Dir::N, Dir::E, Dir::W, Dir::S] {
+    for dir in Dir::CARDINAL {
         if parents.contains_key(&(end, dir)) {
             reconstruct_all_paths(&mut shortest_path_items, &parents, (end, dir));
         }
@@ -4,17 +4,20 @@ use std::{
     fs::read_to_string,
 };
 
-use advent_of_code_2024::{euclidean_distance, Dir, Grid, Loc};
+use advent_of_code_2024::{euclidean_distance, BitGrid, Dir, Grid, Loc};
 
 fn read_input() -> Vec<Loc> {
     read_to_string("input/day18.txt")
         .expect("Input file not found")
         .lines()
         .map(|line| {
-            line.split(",")
+            // lines are x,y, i.e. column then row
+            let (col, row) = line
+                .split(",")
                 .map(|x| x.parse().expect("Could not parse int"))
                 .collect_tuple()
-                .expect("Unable to pase line")
+                .expect("Unable to pase line");
+            Loc(row, col)
         })
         .collect()
 }
@@ -56,7 +59,6 @@ impl Ord for AStarEntry {
 impl Eq for AStarEntry {}
 
 fn a_star_grid(grid: &Grid<char>, start: Loc, end: Loc) -> Option<u32> {
-    use Dir::*;
     let mut pq = BinaryHeap::new();
     pq.push(AStarEntry {
         pos: start,
@@ -64,7 +66,7 @@ fn a_star_grid(grid: &Grid<char>, start: Loc, end: Loc) -> Option<u32> {
         heuristic: euclidean_distance(start, end),
     });
 
-    let mut visited = Grid::new(grid.n_rows(), grid.n_cols(), false);
+    let mut visited = BitGrid::new(grid.n_rows(), grid.n_cols());
     let mut best_dist = Grid::new(grid.n_rows(), grid.n_cols(), u32::MAX);
     best_dist[&start] = 0;
     while !pq.is_empty() {
@@ -72,13 +74,12 @@ fn a_star_grid(grid: &Grid<char>, start: Loc, end: Loc) -> Option<u32> {
         if entry.pos == end {
             return Some(entry.dist);
         }
-        if visited[&entry.pos] {
+        if !visited.insert(&entry.pos) {
             continue;
         }
-        visited[&entry.pos] = true;
         best_dist[&entry.pos] = entry.dist;
 
-        for dir in [N, E, S, W] {
+        for dir in Dir::CARDINAL {
             if let Some(next_pos) = grid.step(&entry.pos, dir) {
                 if grid[&next_pos] == '#' {
                     continue;
@@ -107,7 +108,7 @@ fn part1() -> u32 {
     let bytes = read_input();
     let mut grid = Grid::new(71, 71, '.');
     bytes.iter().take(1024).for_each(|l| grid[l] = '#');
-    a_star_grid(&grid, (0, 0), (70, 70)).unwrap_or(u32::MAX)
+    a_star_grid(&grid, Loc(0, 0), Loc(70, 70)).unwrap_or(u32::MAX)
 }
 
 fn apply_changes(points: &[Loc], n: usize) -> Grid<char> {
@@ -119,7 +120,7 @@ fn apply_changes(points: &[Loc], n: usize) -> Grid<char> {
 }
 
 fn is_valid(grid: &Grid<char>) -> bool {
-    a_star_grid(grid, (0, 0), (70, 70)).is_some()
+    a_star_grid(grid, Loc(0, 0), Loc(70, 70)).is_some()
 }
 
 fn part2() -> String {
@@ -128,8 +129,8 @@ fn part2() -> String {
         let grid = apply_changes(&input, *n);
         is_valid(&grid)
     });
-    let (a, b) = input[num_changes_applied];
-    format!("{},{}", a, b)
+    let Loc(row, col) = input[num_changes_applied];
+    format!("{},{}", col, row)
 }
 
 fn main() {
@@ -7,13 +7,12 @@ fn read_input() -> Grid<char> {
 }
 
 fn find_path(grid: &Grid<char>, start: Loc, end: Loc) -> Vec<Loc> {
-    use Dir::*;
     let mut path = Vec::new();
     let mut curr = start;
     path.push(curr);
 
     'outer: while curr != end {
-        'inner: for d in [N, S, E, W] {
+        'inner: for d in Dir::CARDINAL {
             if let Some(next) = grid.step(&curr, d) {
                 if grid[&next] == '#' {
                     continue 'inner;
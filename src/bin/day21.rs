@@ -1,4 +1,4 @@
-use advent_of_code_2024::{Grid, Loc};
+use advent_of_code_2024::{Dir, Dir4, Grid, Loc, Point};
 use itertools::{iproduct, repeat_n, Itertools};
 use std::cmp::min;
 use std::collections::{HashMap, VecDeque};
@@ -23,8 +23,8 @@ fn directional_keypad() -> &'static Grid<char> {
     DIRECTIONAL_KEYPAD_CELL.get_or_init(|| Grid::from_string(".^A\n<v>"))
 }
 
-const NUMERIC_START: (usize, usize) = (3, 2);
-const DIRECTIONAL_START: (usize, usize) = (0, 2);
+const NUMERIC_START: Loc = Loc(3, 2);
+const DIRECTIONAL_START: Loc = Loc(0, 2);
 
 // get the changes that it would take to print
 fn simulate_reverse_single_layer(
@@ -63,13 +63,8 @@ fn simulate_reverse_single_layer(
                 let mut new_path = original_path.clone();
                 let mut intermediate_pos = ptr_location;
                 for (ch, cnt) in addition {
-                    match ch {
-                        '^' => intermediate_pos.0 -= cnt,
-                        'v' => intermediate_pos.0 += cnt,
-                        '>' => intermediate_pos.1 += cnt,
-                        '<' => intermediate_pos.1 -= cnt,
-                        _ => unreachable!(),
-                    }
+                    let dir = Dir4::try_from(ch).expect("Path only contains arrows");
+                    intermediate_pos = keypad.step_n(&intermediate_pos, dir, cnt)?;
                     // if we pass over the blank space this is illegal
                     if keypad[&intermediate_pos] == '.' {
                         return None;
@@ -143,46 +138,16 @@ fn part1() -> usize {
 
 #[derive(Clone)]
 struct State {
-    pos: Loc,
+    pos: Point,
     sequence: String,
 }
 
 impl State {
-    fn up(&self) -> Self {
-        let (r, c) = self.pos;
-        let mut new_sequence = self.sequence.clone();
-        new_sequence.push('^');
-        Self {
-            pos: (r.checked_sub(1).expect("Called up() on row 0"), c),
-            sequence: new_sequence,
-        }
-    }
-
-    fn down(&self) -> Self {
-        let (r, c) = self.pos;
-        let mut new_sequence = self.sequence.clone();
-        new_sequence.push('v');
-        Self {
-            pos: (r + 1, c),
-            sequence: new_sequence,
-        }
-    }
-    fn left(&self) -> Self {
-        let (r, c) = self.pos;
-        let mut new_sequence = self.sequence.clone();
-        new_sequence.push('<');
-        Self {
-            pos: (r, c.checked_sub(1).expect("called left() on col 0")),
-            sequence: new_sequence,
-        }
-    }
-
-    fn right(&self) -> Self {
-        let (r, c) = self.pos;
+    fn step(&self, dir: Dir4) -> Self {
         let mut new_sequence = self.sequence.clone();
-        new_sequence.push('>');
+        new_sequence.push(dir.to_arrow());
         Self {
-            pos: (r, c + 1),
+            pos: self.pos + Dir::from(dir),
             sequence: new_sequence,
         }
     }
@@ -212,8 +177,9 @@ fn get_transition_cost(
     let mut result = usize::MAX;
     let mut queue = VecDeque::new();
 
+    let end_point = Point::from(end);
     queue.push_back(State {
-        pos: start,
+        pos: Point::from(start),
         sequence: String::new(),
     });
 
@@ -225,7 +191,7 @@ fn get_transition_cost(
             _ => (),
         }
 
-        if state.pos == end {
+        if state.pos == end_point {
             let next_layer = if is_directional { layer - 1 } else { layer };
             let mut sequence = state.sequence;
             sequence.push('A');
@@ -233,14 +199,14 @@ fn get_transition_cost(
             continue;
         }
 
-        match end.0.cmp(&state.pos.0) {
-            std::cmp::Ordering::Less => queue.push_back(state.up()),
-            std::cmp::Ordering::Greater => queue.push_back(state.down()),
+        match end_point.row.cmp(&state.pos.row) {
+            std::cmp::Ordering::Less => queue.push_back(state.step(Dir4::N)),
+            std::cmp::Ordering::Greater => queue.push_back(state.step(Dir4::S)),
             _ => (),
         }
-        match end.1.cmp(&state.pos.1) {
-            std::cmp::Ordering::Less => queue.push_back(state.left()),
-            std::cmp::Ordering::Greater => queue.push_back(state.right()),
+        match end_point.col.cmp(&state.pos.col) {
+            std::cmp::Ordering::Less => queue.push_back(state.step(Dir4::W)),
+            std::cmp::Ordering::Greater => queue.push_back(state.step(Dir4::E)),
             _ => (),
         }
     }
@@ -49,7 +49,7 @@ fn get_next_secret_numbers(start: u64, n: usize) -> Vec<u64> {
     result
 }
 
-fn get_diffs(prices: &Vec<u64>) -> Vec<i32> {
+fn get_diffs(prices: &[u64]) -> Vec<i32> {
     prices
         .iter()
         .tuple_windows()
@@ -1,30 +1,33 @@
+use advent_of_code_2024::bitset::BitSetOps;
+use advent_of_code_2024::fixed_bitset::FixedBitSet;
 use itertools::{iproduct, Itertools};
-use std::{collections::HashMap, fs::read_to_string};
+use std::fs::read_to_string;
 
-type Heights = [u32; 5];
+/** The filled cells of the 5x5 area between the top and bottom rows, as row * 5 + col */
+type Cells = FixedBitSet<1>;
 enum Schematic {
-    Key(Heights),
-    Lock(Heights),
+    Key(Cells),
+    Lock(Cells),
 }
 fn parse_schematic(schematic: Vec<&str>) -> Schematic {
     let is_lock = schematic[0] == "#####";
-    let schematic = &schematic[1..6];
-    let mut heights = [0; 5];
-    for row in schematic {
-        for (col, c) in row.char_indices() {
-            if c == '#' {
-                heights[col] += 1;
-            }
-        }
-    }
+    let cells = schematic[1..6]
+        .iter()
+        .enumerate()
+        .flat_map(|(row, line)| {
+            line.char_indices()
+                .filter(|(_, c)| *c == '#')
+                .map(move |(col, _)| (row * 5 + col) as u64)
+        })
+        .collect();
     if is_lock {
-        Schematic::Lock(heights)
+        Schematic::Lock(cells)
     } else {
-        Schematic::Key(heights)
+        Schematic::Key(cells)
     }
 }
 
-fn read_input() -> (Vec<Heights>, Vec<Heights>) {
+fn read_input() -> (Vec<Cells>, Vec<Cells>) {
     read_to_string("input/day25.txt")
         .unwrap()
         .lines()
@@ -40,8 +43,9 @@ fn read_input() -> (Vec<Heights>, Vec<Heights>) {
         })
 }
 
-fn fits(lock: &Heights, key: &Heights) -> bool {
-    lock.iter().zip(key.iter()).all(|(a, b)| *a + *b <= 5)
+/** A key fits a lock if no pin of the lock overlaps the key's teeth */
+fn fits(lock: &Cells, key: &Cells) -> bool {
+    lock.is_disjoint(key)
 }
 
 fn part_1() -> usize {
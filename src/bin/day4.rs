@@ -1,38 +1,15 @@
 use std::fs::{self};
-use advent_of_code_2024::{Grid,Loc, Dir};
+use advent_of_code_2024::{Grid,Loc, Dir, Point};
 
 fn read_input() -> Grid<char> {
     let input = "input/day4.txt";
     let input = fs::read_to_string(input).unwrap();
     Grid(input.lines().map(|line| line.chars().collect()).collect())
 }
-const DIRS: [Dir; 8] = [
-    Dir::N,
-    Dir::NE,
-    Dir::E,
-    Dir::SE,
-    Dir::S,
-    Dir::SW,
-    Dir::W,
-    Dir::NW,
-];
 
 
-fn check_add(loc: Loc, delta: (i32, i32)) -> Option<Loc> {
-    let (r, c) = loc;
-    let (dr, dc) = delta;
-
-    if (r == 0 && dr < 0)  || (c == 0 && dc < 0){
-        None
-    } else {
-        let nr = ((r as i32) + dr) as usize;
-        let nc = ((c as i32) + dc) as usize;
-        Some((nr, nc))
-    }
-}
-
 fn check_word_in_dir(grid: &Grid<char>, dir: Dir, target: &str, start_pos: Loc) -> bool {
-    let mut curr = start_pos;
+    let mut curr = Point::from(start_pos);
     let mut word = String::new();
 
     for _ in 0..target.len() {
@@ -40,11 +17,7 @@ fn check_word_in_dir(grid: &Grid<char>, dir: Dir, target: &str, start_pos: Loc)
             None => break,
             Some(c) => word.push(*c),
         }
-        if let Some(new_pos) = check_add(curr, dir.delta()) {
-            curr = new_pos
-        } else {
-            break;
-        }
+        curr += dir;
     }
 
     word == target
@@ -55,8 +28,8 @@ fn part1() -> i32 {
     let mut result = 0;
     for r in 0..grid.n_rows() {
         for c in 0..grid.n_cols() {
-            for dir in DIRS {
-                if check_word_in_dir(&grid, dir, "XMAS", (r, c)) {
+            for dir in Dir::ALL {
+                if check_word_in_dir(&grid, dir, "XMAS", Loc(r, c)) {
                     result += 1
                 }
             }
@@ -72,10 +45,10 @@ fn part2() -> i32 {
     // top left corner of pattern
     for r in 0..grid.n_rows() - 2 {
         for c in 0..grid.n_cols() - 2 {
-            if (check_word_in_dir(&grid, Dir::SE, "MAS", (r, c))
-                || check_word_in_dir(&grid, Dir::SE, "SAM", (r, c)))
-                && (check_word_in_dir(&grid, Dir::SW, "MAS", (r, c + 2))
-                    || check_word_in_dir(&grid, Dir::SW, "SAM", (r, c + 2)))
+            if (check_word_in_dir(&grid, Dir::SE, "MAS", Loc(r, c))
+                || check_word_in_dir(&grid, Dir::SE, "SAM", Loc(r, c)))
+                && (check_word_in_dir(&grid, Dir::SW, "MAS", Loc(r, c + 2))
+                    || check_word_in_dir(&grid, Dir::SW, "SAM", Loc(r, c + 2)))
             {
                 result += 1;
             }
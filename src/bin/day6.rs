@@ -1,5 +1,5 @@
-use advent_of_code_2024::{Dir, Grid, Loc};
-use std::{collections::HashSet, fs};
+use advent_of_code_2024::{BitGrid, Dir, Grid, Loc, Turn};
+use std::fs;
 
 fn read_input() -> Grid<char> {
     Grid::from_string(&fs::read_to_string("input/day6.txt").unwrap())
@@ -16,7 +16,7 @@ fn next_guard_position(grid: &Grid<char>, pos: &Loc, mut facing: Dir) -> Option<
                 }
             }
         }
-        facing = facing.rotate_clockwise();
+        facing = facing.apply(Turn::Right);
     }
     Some((*pos, facing))
 }
@@ -25,7 +25,7 @@ fn find_guard(grid: &Grid<char>) -> Option<(Loc, Dir)> {
     for (r, row) in grid.iter_rows().enumerate() {
         for (c, ch) in row.iter().enumerate() {
             if *ch == '^' {
-                return Some(((r, c), Dir::N));
+                return Some((Loc(r, c), Dir::N));
             }
         }
     }
@@ -33,17 +33,13 @@ fn find_guard(grid: &Grid<char>) -> Option<(Loc, Dir)> {
 }
 
 fn part1() -> u32 {
-    let mut grid = read_input();
+    let grid = read_input();
+    let mut visited = BitGrid::new(grid.n_rows(), grid.n_cols());
     // find the guard
     let mut guard_loc: Option<(Loc, Dir)> = find_guard(&grid);
 
-    let mut num_visited = 0;
     while let Some((pos, facing)) = &guard_loc {
-        let ch = grid.at_mut(pos).unwrap();
-        if *ch != 'X' {
-            *ch = 'X';
-            num_visited += 1;
-        }
+        visited.insert(pos);
         let new_loc = next_guard_position(&grid, pos, *facing);
         if let Some((new_pos, _)) = &new_loc {
             if new_pos == pos {
@@ -53,7 +49,7 @@ fn part1() -> u32 {
         guard_loc = new_loc;
     }
 
-    num_visited
+    visited.count_ones() as u32
 }
 
 fn jump_to_next_obstacle(
@@ -72,13 +68,13 @@ fn jump_to_next_obstacle(
 }
 
 fn gets_stuck_in_loop(grid: &Grid<char>, start_pos: &Loc, start_dir: Dir) -> bool {
-    let mut seen: HashSet<(Loc, Dir)> = HashSet::new();
+    // one layer per heading; the guard only ever faces N, E, S or W, i.e. 0, 90, 180 or 270
+    let mut seen = vec![BitGrid::new(grid.n_rows(), grid.n_cols()); 4];
     let mut curr_pos = Some((*start_pos, start_dir));
     while let Some((loc, dir)) = curr_pos {
-        if seen.contains(&(loc, dir)) {
+        if !seen[(dir.to_degrees() / 90) as usize].insert(&loc) {
             return true;
         }
-        seen.insert((loc, dir));
         curr_pos = next_guard_position(grid, &loc, dir)
             .and_then(|(pos, facing)| jump_to_next_obstacle(grid, &pos, facing));
     }
@@ -1,3 +1,4 @@
+use advent_of_code_2024::math::Digits;
 use std::fs::read_to_string;
 
 fn read_input() -> Vec<(u64, Vec<u64>)> {
@@ -16,7 +17,7 @@ fn read_input() -> Vec<(u64, Vec<u64>)> {
         .collect()
 }
 
-fn get_operator_result(nums: &Vec<u64>, bitmask: u32) -> u64 {
+fn get_operator_result(nums: &[u64], bitmask: u32) -> u64 {
     if nums.len() == 1 {
         return nums[0];
     }
@@ -56,36 +57,31 @@ enum Op {
 }
 
 impl Op {
-    fn apply(&self, lhs: u64, rhs: u64) -> u64 {
+    // None if the result overflows, which is then certainly above the target
+    fn apply(&self, lhs: u64, rhs: u64) -> Option<u64> {
         match self {
-            Op::Add => lhs + rhs,
-            Op::Multiply => lhs * rhs,
-            Op::Concatenate => {
-                let mut p = 1;
-                while p <= rhs {
-                    p *= 10;
-                }
-                p * lhs + rhs
-            }
+            Op::Add => lhs.checked_add(rhs),
+            Op::Multiply => lhs.checked_mul(rhs),
+            Op::Concatenate => lhs.concat_digits(rhs),
         }
     }
 }
 
 // switch to recursive backtracking
-fn can_make_target(target: u64, nums: &Vec<u64>) -> bool {
+fn can_make_target(target: u64, nums: &[u64]) -> bool {
     can_make_target_helper(target, nums, nums[0], 1)
 }
 
-fn can_make_target_helper(target: u64, nums: &Vec<u64>, partial_result: u64, idx: usize) -> bool {
+fn can_make_target_helper(target: u64, nums: &[u64], partial_result: u64, idx: usize) -> bool {
     if idx == nums.len() {
         return partial_result == target;
     }
 
     for op in [Op::Add, Op::Multiply, Op::Concatenate] {
         let next_partial_result = op.apply(partial_result, nums[idx]);
-        if next_partial_result <= target
-            && can_make_target_helper(target, nums, next_partial_result, idx + 1)
-        {
+        if next_partial_result.is_some_and(|next| {
+            next <= target && can_make_target_helper(target, nums, next, idx + 1)
+        }) {
             return true;
         }
     }
@@ -1,13 +1,43 @@
 mod lib {
+    pub mod adder;
+    pub mod bitgrid;
+    pub mod bitmatrix;
     pub mod bitset;
+    pub mod circuit;
+    pub mod dag;
     pub mod dir;
+    pub mod fixed_bitset;
     pub mod grid;
+    pub mod grid3;
+    pub mod linalg;
+    pub mod line;
     pub mod loc;
+    pub mod math;
+    pub mod rect;
+    pub mod sparse_bitset;
+    pub mod threebit;
     pub mod vec2;
+    pub mod vec3;
+    pub mod vecn;
 }
 
+pub use lib::adder;
+pub use lib::bitgrid::*;
+pub use lib::bitmatrix::*;
 pub use lib::bitset;
+pub use lib::circuit;
+pub use lib::dag::*;
 pub use lib::dir::*;
+pub use lib::fixed_bitset;
 pub use lib::grid::*;
+pub use lib::grid3::*;
+pub use lib::linalg;
+pub use lib::line::*;
 pub use lib::loc::*;
+pub use lib::math;
+pub use lib::rect::*;
+pub use lib::sparse_bitset;
+pub use lib::threebit;
 pub use lib::vec2::*;
+pub use lib::vec3::*;
+pub use lib::vecn::*;
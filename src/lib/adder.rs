@@ -0,0 +1,448 @@
+use crate::circuit::{GateOp, Netlist, WireId};
+use std::fmt::{self, Display};
+
+/** The gates of one full-adder stage, adding `x_i`, `y_i` and the carry from bit `i - 1` */
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Role {
+    /** `x_i ^ y_i` */
+    HalfSum,
+    /** `x_i & y_i` */
+    HalfCarry,
+    /** `half_sum ^ carry_in`, driving `z_i` */
+    Sum,
+    /** `half_sum & carry_in` */
+    PropagatedCarry,
+    /** `half_carry | propagated_carry`, the carry into bit `i + 1` */
+    CarryOut,
+}
+
+impl Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Role::HalfSum => "x ^ y",
+            Role::HalfCarry => "x & y",
+            Role::Sum => "sum XOR",
+            Role::PropagatedCarry => "carry AND",
+            Role::CarryOut => "carry OR",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Fault {
+    /** No gate with the inputs and operator the stage needs */
+    Missing { bit: usize, role: Role },
+    /** The gate for `role` drives `found`, but its signal is needed on `expected` */
+    Misrouted {
+        bit: usize,
+        role: Role,
+        found: WireId,
+        expected: WireId,
+    },
+}
+
+impl Fault {
+    pub fn bit(&self) -> usize {
+        match self {
+            Fault::Missing { bit, .. } | Fault::Misrouted { bit, .. } => *bit,
+        }
+    }
+
+    pub fn describe(&self, netlist: &Netlist) -> String {
+        match self {
+            Fault::Missing { bit, role } => format!("bit {}: no {} gate", bit, role),
+            Fault::Misrouted {
+                bit,
+                role,
+                found,
+                expected,
+            } => format!(
+                "bit {}: {} gate drives {} instead of {}",
+                bit,
+                role,
+                netlist.name(*found),
+                netlist.name(*expected)
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AdderError {
+    /** `x` and `y` must be the same non-zero width and `z` one bit wider */
+    BusWidths { x: usize, y: usize, z: usize },
+}
+
+impl Display for AdderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AdderError::BusWidths { x, y, z } => write!(
+                f,
+                "bus widths x={}, y={}, z={} do not form an adder",
+                x, y, z
+            ),
+        }
+    }
+}
+
+impl std::error::Error for AdderError {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnosis {
+    pub width: usize,
+    /** Faults in the order they were found, each one after the swaps before it were made */
+    pub faults: Vec<Fault>,
+    /** Output swaps that repair the misrouted faults */
+    pub swaps: Vec<(WireId, WireId)>,
+    /** How many low bits are a correct adder once `swaps` are made */
+    pub verified_bits: usize,
+}
+
+impl Diagnosis {
+    /** True if the swaps turn the netlist into a correct adder */
+    pub fn is_repaired(&self) -> bool {
+        self.verified_bits == self.width
+    }
+
+    /** The names of every swapped wire, sorted */
+    pub fn swapped_wires<'a>(&self, netlist: &'a Netlist) -> Vec<&'a str> {
+        let mut names: Vec<&str> = self
+            .swaps
+            .iter()
+            .flat_map(|(a, b)| [netlist.name(*a), netlist.name(*b)])
+            .collect();
+        names.sort();
+        names
+    }
+}
+
+type StageResult = Result<WireId, (Fault, Option<(WireId, WireId)>)>;
+
+/**
+* Checks that the netlist is a ripple-carry adder computing `z = x + y`, one stage at a time
+* from bit 0. Each stage is matched against the expected gates by their inputs, so only
+* the wires between stages need to be known, and the width comes from the `x` bus.
+* Misrouted gates are repaired by swapping outputs before moving on; a fault that no swap
+* can fix stops the check.
+*/
+pub fn diagnose(netlist: &Netlist) -> Result<Diagnosis, AdderError> {
+    let (x, y, z) = (netlist.bus("x"), netlist.bus("y"), netlist.bus("z"));
+    if x.is_empty() || x.len() != y.len() || z.len() != x.len() + 1 {
+        return Err(AdderError::BusWidths {
+            x: x.len(),
+            y: y.len(),
+            z: z.len(),
+        });
+    }
+
+    let width = x.len();
+    let mut diagnosis = Diagnosis {
+        width,
+        faults: Vec::new(),
+        swaps: Vec::new(),
+        verified_bits: 0,
+    };
+    let mut repaired = netlist.clone();
+    let mut carry = None;
+    'stages: for bit in 0..width {
+        let final_carry = (bit == width - 1).then_some(z[width]);
+        loop {
+            match check_stage(&repaired, bit, [x[bit], y[bit], z[bit]], carry, final_carry) {
+                Ok(carry_out) => {
+                    carry = Some(carry_out);
+                    break;
+                }
+                Err((fault, swap)) => {
+                    diagnosis.faults.push(fault);
+                    let Some((a, b)) = swap else {
+                        break 'stages;
+                    };
+                    // give up rather than undo an earlier repair or close a loop
+                    if diagnosis.swaps.iter().any(|s| *s == (a, b) || *s == (b, a)) {
+                        break 'stages;
+                    }
+                    repaired.swap_outputs(a, b);
+                    if repaired.find_loop().is_some() {
+                        repaired.swap_outputs(a, b);
+                        break 'stages;
+                    }
+                    diagnosis.swaps.push((a, b));
+                    carry = carry.map(|c| swapped(c, a, b));
+                }
+            }
+        }
+    }
+    // a swap can move a wire an earlier stage relies on, so check every stage again
+    diagnosis.verified_bits = verified_bits(&repaired, [&x, &y, &z]);
+    Ok(diagnosis)
+}
+
+/** How many stages from bit 0 up are correct, without repairing anything */
+fn verified_bits(netlist: &Netlist, [x, y, z]: [&[WireId]; 3]) -> usize {
+    let width = x.len();
+    let mut carry = None;
+    for bit in 0..width {
+        let final_carry = (bit == width - 1).then_some(z[width]);
+        match check_stage(netlist, bit, [x[bit], y[bit], z[bit]], carry, final_carry) {
+            Ok(carry_out) => carry = Some(carry_out),
+            Err(_) => return bit,
+        }
+    }
+    width
+}
+
+fn swapped(wire: WireId, a: WireId, b: WireId) -> WireId {
+    match wire {
+        w if w == a => b,
+        w if w == b => a,
+        w => w,
+    }
+}
+
+/** The other input of `op` gates reading `wire`, if there is exactly one such gate */
+fn partner(netlist: &Netlist, wire: WireId, op: GateOp) -> Option<WireId> {
+    let mut readers = netlist.readers(wire).filter(|g| g.op == op);
+    let gate = readers.next()?;
+    if readers.next().is_some() {
+        return None;
+    }
+    gate.inputs.into_iter().find(|w| *w != wire)
+}
+
+/**
+* Checks one stage, returning its carry out, or the first fault with the swap that
+* fixes it. `carry_in` is None for the half adder at bit 0, and `final_carry` is
+* the top `z` wire that the last stage's carry must drive.
+*/
+fn check_stage(
+    netlist: &Netlist,
+    bit: usize,
+    [x, y, z]: [WireId; 3],
+    carry_in: Option<WireId>,
+    final_carry: Option<WireId>,
+) -> StageResult {
+    use GateOp::*;
+    let missing = |role| (Fault::Missing { bit, role }, None);
+    let misrouted = |bit, role, found, expected| {
+        let fault = Fault::Misrouted {
+            bit,
+            role,
+            found,
+            expected,
+        };
+        (fault, Some((found, expected)))
+    };
+
+    let half_sum = netlist
+        .find_gate(x, Xor, y)
+        .ok_or(missing(Role::HalfSum))?
+        .output;
+    let half_carry = netlist
+        .find_gate(x, And, y)
+        .ok_or(missing(Role::HalfCarry))?
+        .output;
+
+    let carry_out = match carry_in {
+        None => {
+            if half_sum != z {
+                return Err(misrouted(bit, Role::HalfSum, half_sum, z));
+            }
+            half_carry
+        }
+        Some(carry_in) => {
+            let Some(sum) = netlist.find_gate(half_sum, Xor, carry_in) else {
+                // the sum gate reads the right wire on one input, so the other is misrouted
+                let carry_role = if bit == 1 {
+                    Role::HalfCarry
+                } else {
+                    Role::CarryOut
+                };
+                if let Some(other) = partner(netlist, half_sum, Xor) {
+                    return Err(misrouted(bit - 1, carry_role, carry_in, other));
+                }
+                if let Some(other) = partner(netlist, carry_in, Xor) {
+                    return Err(misrouted(bit, Role::HalfSum, half_sum, other));
+                }
+                return Err(missing(Role::Sum));
+            };
+            if sum.output != z {
+                return Err(misrouted(bit, Role::Sum, sum.output, z));
+            }
+
+            let propagated = netlist
+                .find_gate(half_sum, And, carry_in)
+                .ok_or(missing(Role::PropagatedCarry))?
+                .output;
+            let Some(carry_gate) = netlist.find_gate(half_carry, Or, propagated) else {
+                if let Some(other) = partner(netlist, half_carry, Or) {
+                    return Err(misrouted(bit, Role::PropagatedCarry, propagated, other));
+                }
+                if let Some(other) = partner(netlist, propagated, Or) {
+                    return Err(misrouted(bit, Role::HalfCarry, half_carry, other));
+                }
+                return Err(missing(Role::CarryOut));
+            };
+            carry_gate.output
+        }
+    };
+
+    match final_carry {
+        Some(expected) if carry_out != expected => {
+            let role = if bit == 0 {
+                Role::HalfCarry
+            } else {
+                Role::CarryOut
+            };
+            Err(misrouted(bit, role, carry_out, expected))
+        }
+        _ => Ok(carry_out),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::adder::*;
+
+    /** A correct adder with generated names for the internal wires */
+    fn ripple_carry(width: usize) -> Netlist {
+        let mut lines = Vec::new();
+        let mut carry = String::new();
+        for bit in 0..width {
+            let (x, y, z) = (
+                format!("x{:02}", bit),
+                format!("y{:02}", bit),
+                format!("z{:02}", bit),
+            );
+            let carry_out = if bit == width - 1 {
+                format!("z{:02}", width)
+            } else {
+                format!("c{:02}", bit)
+            };
+            if bit == 0 {
+                lines.push(format!("{} XOR {} -> {}", x, y, z));
+                lines.push(format!("{} AND {} -> {}", x, y, carry_out));
+            } else {
+                lines.push(format!("{} XOR {} -> s{:02}", x, y, bit));
+                lines.push(format!("{} AND {} -> h{:02}", y, x, bit));
+                lines.push(format!("s{:02} XOR {} -> {}", bit, carry, z));
+                lines.push(format!("{} AND s{:02} -> p{:02}", carry, bit, bit));
+                lines.push(format!("h{:02} OR p{:02} -> {}", bit, bit, carry_out));
+            }
+            carry = carry_out;
+        }
+        let mut netlist = Netlist::parse(&lines.join("\n")).unwrap();
+        netlist.set_bus("x", 0).unwrap();
+        netlist.set_bus("y", 0).unwrap();
+        netlist
+    }
+
+    fn swap(netlist: &mut Netlist, a: &str, b: &str) {
+        let (a, b) = (netlist.id(a).unwrap(), netlist.id(b).unwrap());
+        netlist.swap_outputs(a, b);
+    }
+
+    #[test]
+    fn test_correct_adder() {
+        for width in [1, 2, 8] {
+            let mut netlist = ripple_carry(width);
+            let diagnosis = diagnose(&netlist).unwrap();
+            assert!(diagnosis.is_repaired() && diagnosis.faults.is_empty());
+            assert_eq!(diagnosis.width, width);
+
+            let max = (1 << width) - 1;
+            netlist.set_bus("x", max).unwrap();
+            netlist.set_bus("y", max / 3).unwrap();
+            assert_eq!(netlist.evaluate_bus("z"), Ok(max + max / 3));
+        }
+    }
+
+    #[test]
+    fn test_repairs() {
+        let mut netlist = ripple_carry(8);
+        swap(&mut netlist, "s02", "h02");
+        swap(&mut netlist, "z03", "c03");
+        swap(&mut netlist, "z05", "p05");
+        swap(&mut netlist, "z06", "h06");
+        let diagnosis = diagnose(&netlist).unwrap();
+        assert!(diagnosis.is_repaired());
+        assert_eq!(
+            diagnosis.swapped_wires(&netlist),
+            ["c03", "h02", "h06", "p05", "s02", "z03", "z05", "z06"]
+        );
+        assert_eq!(
+            diagnosis.faults.iter().map(|f| f.bit()).collect::<Vec<_>>(),
+            [2, 3, 5, 6]
+        );
+        assert_eq!(
+            diagnosis.faults[0].describe(&netlist),
+            "bit 2: x ^ y gate drives h02 instead of s02"
+        );
+
+        for (a, b) in diagnosis.swaps {
+            netlist.swap_outputs(a, b);
+        }
+        netlist.set_bus("x", 200).unwrap();
+        netlist.set_bus("y", 99).unwrap();
+        assert_eq!(netlist.evaluate_bus("z"), Ok(299));
+    }
+
+    #[test]
+    fn test_first_and_last_stage() {
+        let mut netlist = ripple_carry(4);
+        swap(&mut netlist, "z00", "c00");
+        swap(&mut netlist, "z04", "z03");
+        let diagnosis = diagnose(&netlist).unwrap();
+        assert!(diagnosis.is_repaired());
+        assert_eq!(
+            diagnosis.swapped_wires(&netlist),
+            ["c00", "z00", "z03", "z04"]
+        );
+    }
+
+    #[test]
+    fn test_swap_breaks_earlier_stage() {
+        // bit 2's sum reads z00 instead of c01, so the proposed swap moves bit 0's sum
+        let lines = [
+            "x00 XOR y00 -> z00",
+            "x00 AND y00 -> c00",
+            "x01 XOR y01 -> s01",
+            "y01 AND x01 -> h01",
+            "s01 XOR c00 -> z01",
+            "c00 AND s01 -> p01",
+            "h01 OR p01 -> c01",
+            "x02 XOR y02 -> s02",
+            "y02 AND x02 -> h02",
+            "s02 XOR z00 -> z02",
+            "c01 AND s02 -> p02",
+            "h02 OR p02 -> z03",
+        ];
+        let netlist = Netlist::parse(&lines.join("\n")).unwrap();
+        let diagnosis = diagnose(&netlist).unwrap();
+        assert_eq!(diagnosis.swapped_wires(&netlist), ["c01", "z00"]);
+        // stage 0 passed before the swap, but the swap moved its sum off z00
+        assert_eq!(diagnosis.verified_bits, 0);
+        assert!(!diagnosis.is_repaired());
+    }
+
+    #[test]
+    fn test_unrepairable() {
+        let netlist =
+            Netlist::parse("x00: 1\ny00: 1\nx00 XOR y00 -> z00\nx00 OR y00 -> z01").unwrap();
+        let diagnosis = diagnose(&netlist).unwrap();
+        assert!(!diagnosis.is_repaired());
+        assert_eq!(
+            diagnosis.faults,
+            [Fault::Missing {
+                bit: 0,
+                role: Role::HalfCarry
+            }]
+        );
+
+        let netlist = Netlist::parse("x00: 1\ny00: 1\nx00 XOR y00 -> z00").unwrap();
+        assert_eq!(
+            diagnose(&netlist),
+            Err(AdderError::BusWidths { x: 1, y: 1, z: 1 })
+        );
+    }
+}
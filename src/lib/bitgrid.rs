@@ -0,0 +1,305 @@
+use std::ops::Index;
+
+use crate::bitset::BitSetIter;
+use crate::{Grid, Loc};
+
+/**
+* A 2D grid of booleans stored one bit per cell, with the same word layout as `BitSet`.
+* Every row starts on a fresh word, so rows can be combined and shifted a word at a time.
+*/
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    n_rows: usize,
+    n_cols: usize,
+    words_per_row: usize,
+    words: Vec<u64>,
+}
+
+impl BitGrid {
+    pub fn new(n_rows: usize, n_cols: usize) -> Self {
+        let words_per_row = n_cols.div_ceil(64);
+        Self {
+            n_rows,
+            n_cols,
+            words_per_row,
+            words: vec![0; n_rows * words_per_row],
+        }
+    }
+
+    pub fn n_rows(&self) -> usize {
+        self.n_rows
+    }
+
+    pub fn n_cols(&self) -> usize {
+        self.n_cols
+    }
+
+    pub fn in_bounds(&self, loc: &Loc) -> bool {
+        let Loc(r, c) = *loc;
+        r < self.n_rows && c < self.n_cols
+    }
+
+    fn position(&self, loc: &Loc) -> (usize, u64) {
+        assert!(
+            self.in_bounds(loc),
+            "{:?} is outside of a {}x{} BitGrid",
+            loc,
+            self.n_rows,
+            self.n_cols
+        );
+        let Loc(r, c) = *loc;
+        (r * self.words_per_row + c / 64, 1 << (c % 64))
+    }
+
+    /** false for locations outside of the grid */
+    pub fn contains(&self, loc: &Loc) -> bool {
+        self.in_bounds(loc) && {
+            let (word, mask) = self.position(loc);
+            self.words[word] & mask != 0
+        }
+    }
+
+    /** Sets the cell, returning true if it was not already set */
+    pub fn insert(&mut self, loc: &Loc) -> bool {
+        let (word, mask) = self.position(loc);
+        let was_unset = self.words[word] & mask == 0;
+        self.words[word] |= mask;
+        was_unset
+    }
+
+    /** Clears the cell, returning true if it was set */
+    pub fn remove(&mut self, loc: &Loc) -> bool {
+        let (word, mask) = self.position(loc);
+        let was_set = self.words[word] & mask != 0;
+        self.words[word] &= !mask;
+        was_set
+    }
+
+    pub fn set(&mut self, loc: &Loc, value: bool) {
+        if value {
+            self.insert(loc);
+        } else {
+            self.remove(loc);
+        }
+    }
+
+    /** Clears every cell, keeping the allocation */
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    /** Number of set cells */
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /** The words backing row `r`, in `BitSet` layout */
+    pub fn row_words(&self, r: usize) -> &[u64] {
+        &self.words[r * self.words_per_row..(r + 1) * self.words_per_row]
+    }
+
+    /** Iterates over the locations of set cells in row-major order */
+    pub fn iter(&self) -> impl Iterator<Item = Loc> + '_ {
+        (0..self.n_rows)
+            .flat_map(move |r| BitSetIter::new(self.row_words(r)).map(move |c| Loc(r, c as usize)))
+    }
+
+    fn combine_inplace<F: Fn(&mut u64, u64)>(&mut self, other: &BitGrid, op: F) {
+        assert_eq!(
+            (self.n_rows, self.n_cols),
+            (other.n_rows, other.n_cols),
+            "BitGrid dimensions differ"
+        );
+        for (target, val) in self.words.iter_mut().zip(other.words.iter()) {
+            op(target, *val);
+        }
+    }
+
+    pub fn and_inplace(&mut self, other: &BitGrid) {
+        self.combine_inplace(other, |a, b| *a &= b);
+    }
+
+    pub fn or_inplace(&mut self, other: &BitGrid) {
+        self.combine_inplace(other, |a, b| *a |= b);
+    }
+
+    pub fn xor_inplace(&mut self, other: &BitGrid) {
+        self.combine_inplace(other, |a, b| *a ^= b);
+    }
+
+    /** Clears the padding bits past the last column of every row */
+    fn mask_padding(&mut self) {
+        let extra = self.words_per_row * 64 - self.n_cols;
+        if extra == 0 {
+            return;
+        }
+        let mask = u64::MAX >> extra;
+        for row in self.words.chunks_mut(self.words_per_row) {
+            *row.last_mut().unwrap() &= mask;
+        }
+    }
+
+    /**
+     * Moves every cell `n` columns east (or west, if negative) within its row.
+     * Cells shifted past the edge are dropped.
+     */
+    pub fn shift_cols(&mut self, n: isize) {
+        if self.words_per_row == 0 {
+            return;
+        }
+        let amount = n.unsigned_abs();
+        for row in self.words.chunks_mut(self.words_per_row) {
+            if n >= 0 {
+                shift_words_up(row, amount);
+            } else {
+                shift_words_down(row, amount);
+            }
+        }
+        self.mask_padding();
+    }
+
+    /**
+     * Moves every row `n` rows south (or north, if negative).
+     * Rows shifted past the edge are dropped.
+     */
+    pub fn shift_rows(&mut self, n: isize) {
+        let amount = n
+            .unsigned_abs()
+            .saturating_mul(self.words_per_row)
+            .min(self.words.len());
+        let len = self.words.len();
+        if n >= 0 {
+            self.words.copy_within(..len - amount, amount);
+            self.words[..amount].fill(0);
+        } else {
+            self.words.copy_within(amount.., 0);
+            self.words[len - amount..].fill(0);
+        }
+    }
+}
+
+/** shifts bits towards higher positions, as if the slice were one little-endian integer */
+fn shift_words_up(words: &mut [u64], n: usize) {
+    let (word_shift, bit_shift) = (n / 64, n % 64);
+    for i in (0..words.len()).rev() {
+        let lo = i.checked_sub(word_shift).map_or(0, |j| words[j]);
+        let carry = match (bit_shift, i.checked_sub(word_shift + 1)) {
+            (0, _) | (_, None) => 0,
+            (_, Some(j)) => words[j] >> (64 - bit_shift),
+        };
+        words[i] = (lo << bit_shift) | carry;
+    }
+}
+
+/** shifts bits towards lower positions, as if the slice were one little-endian integer */
+fn shift_words_down(words: &mut [u64], n: usize) {
+    let (word_shift, bit_shift) = (n / 64, n % 64);
+    for i in 0..words.len() {
+        let hi = words.get(i + word_shift).copied().unwrap_or(0);
+        let carry = match bit_shift {
+            0 => 0,
+            _ => words.get(i + word_shift + 1).copied().unwrap_or(0) << (64 - bit_shift),
+        };
+        words[i] = (hi >> bit_shift) | carry;
+    }
+}
+
+impl Index<&Loc> for BitGrid {
+    type Output = bool;
+
+    fn index(&self, index: &Loc) -> &Self::Output {
+        let (word, mask) = self.position(index);
+        if self.words[word] & mask != 0 {
+            &true
+        } else {
+            &false
+        }
+    }
+}
+
+impl From<&Grid<bool>> for BitGrid {
+    fn from(grid: &Grid<bool>) -> Self {
+        let mut bits = BitGrid::new(grid.n_rows(), grid.n_cols());
+        for (loc, value) in grid.iter_with_loc() {
+            if *value {
+                bits.insert(&loc);
+            }
+        }
+        bits
+    }
+}
+
+impl From<&BitGrid> for Grid<bool> {
+    fn from(bits: &BitGrid) -> Self {
+        let mut grid = Grid::new(bits.n_rows(), bits.n_cols(), false);
+        for loc in bits.iter() {
+            grid[&loc] = true;
+        }
+        grid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_contains() {
+        let mut g = BitGrid::new(3, 70);
+        assert!(g.insert(&Loc(1, 65)));
+        assert!(!g.insert(&Loc(1, 65)));
+        assert!(g.insert(&Loc(2, 0)));
+        assert!(g[&Loc(1, 65)]);
+        assert!(!g[&Loc(0, 65)]);
+        assert!(!g.contains(&Loc(5, 5)));
+        assert_eq!(g.count_ones(), 2);
+        assert_eq!(g.iter().collect::<Vec<_>>(), vec![Loc(1, 65), Loc(2, 0)]);
+        g.clear();
+        assert_eq!(g.count_ones(), 0);
+    }
+
+    #[test]
+    fn test_shift_cols() {
+        let mut g = BitGrid::new(2, 70);
+        g.insert(&Loc(0, 0));
+        g.insert(&Loc(0, 62));
+        g.insert(&Loc(1, 69));
+        g.shift_cols(3);
+        assert_eq!(g.iter().collect::<Vec<_>>(), vec![Loc(0, 3), Loc(0, 65)]);
+        g.shift_cols(-65);
+        assert_eq!(g.iter().collect::<Vec<_>>(), vec![Loc(0, 0)]);
+    }
+
+    #[test]
+    fn test_shift_rows() {
+        let mut g = BitGrid::new(3, 5);
+        g.insert(&Loc(0, 1));
+        g.insert(&Loc(2, 4));
+        g.shift_rows(1);
+        assert_eq!(g.iter().collect::<Vec<_>>(), vec![Loc(1, 1)]);
+        g.shift_rows(-1);
+        assert_eq!(g.iter().collect::<Vec<_>>(), vec![Loc(0, 1)]);
+        g.shift_rows(10);
+        assert_eq!(g.count_ones(), 0);
+        g.insert(&Loc(1, 1));
+        g.shift_rows(isize::MIN);
+        assert_eq!(g.count_ones(), 0);
+        g.insert(&Loc(1, 1));
+        g.shift_rows(isize::MAX);
+        assert_eq!(g.count_ones(), 0);
+    }
+
+    #[test]
+    fn test_bulk_ops_and_conversion() {
+        let a = BitGrid::from(&Grid(vec![vec![true, true], vec![false, true]]));
+        let b = BitGrid::from(&Grid(vec![vec![false, true], vec![true, true]]));
+        let mut x = a.clone();
+        x.xor_inplace(&b);
+        assert_eq!(Grid::from(&x).0, vec![vec![true, false], vec![true, false]]);
+        let mut y = a.clone();
+        y.and_inplace(&b);
+        assert_eq!(y.count_ones(), 2);
+        y.or_inplace(&x);
+        assert_eq!(y.count_ones(), 4);
+    }
+}
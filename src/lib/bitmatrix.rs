@@ -0,0 +1,374 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::bitset::{BitSet, BitSetIter};
+
+/**
+* A dense boolean matrix stored one bit per entry, with each row in `BitSet` word layout.
+* Mostly used as the adjacency matrix of a graph, where `contains(u, v)` means an edge u -> v.
+*/
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BitMatrix {
+    n_rows: usize,
+    n_cols: usize,
+    words_per_row: usize,
+    words: Vec<u64>,
+}
+
+impl BitMatrix {
+    pub fn new(n_rows: usize, n_cols: usize) -> Self {
+        let words_per_row = n_cols.div_ceil(64);
+        Self {
+            n_rows,
+            n_cols,
+            words_per_row,
+            words: vec![0; n_rows * words_per_row],
+        }
+    }
+
+    /** An n x n matrix, as used for the adjacency of a graph with n vertices */
+    pub fn square(n: usize) -> Self {
+        Self::new(n, n)
+    }
+
+    /**
+     * Builds the adjacency matrix of an undirected graph from its edges.
+     * Vertices are numbered in order of first appearance, which is also the order of the
+     * returned labels.
+     */
+    pub fn from_undirected_edges<T, I>(edges: I) -> (Vec<T>, BitMatrix)
+    where
+        T: Clone + Eq + Hash,
+        I: IntoIterator<Item = (T, T)>,
+    {
+        let mut ids: HashMap<T, usize> = HashMap::new();
+        let mut labels = Vec::new();
+        let mut id_of = |label: T| {
+            *ids.entry(label.clone()).or_insert_with(|| {
+                labels.push(label);
+                labels.len() - 1
+            })
+        };
+        let edges: Vec<(usize, usize)> = edges
+            .into_iter()
+            .map(|(u, v)| (id_of(u), id_of(v)))
+            .collect();
+
+        let mut matrix = BitMatrix::square(labels.len());
+        for (u, v) in edges {
+            matrix.insert_symmetric(u, v);
+        }
+        (labels, matrix)
+    }
+
+    pub fn n_rows(&self) -> usize {
+        self.n_rows
+    }
+
+    pub fn n_cols(&self) -> usize {
+        self.n_cols
+    }
+
+    fn position(&self, r: usize, c: usize) -> (usize, u64) {
+        assert!(
+            r < self.n_rows && c < self.n_cols,
+            "({}, {}) is outside of a {}x{} BitMatrix",
+            r,
+            c,
+            self.n_rows,
+            self.n_cols
+        );
+        (r * self.words_per_row + c / 64, 1 << (c % 64))
+    }
+
+    pub fn contains(&self, r: usize, c: usize) -> bool {
+        let (word, mask) = self.position(r, c);
+        self.words[word] & mask != 0
+    }
+
+    pub fn insert(&mut self, r: usize, c: usize) {
+        let (word, mask) = self.position(r, c);
+        self.words[word] |= mask;
+    }
+
+    pub fn remove(&mut self, r: usize, c: usize) {
+        let (word, mask) = self.position(r, c);
+        self.words[word] &= !mask;
+    }
+
+    /** Sets both (a, b) and (b, a), i.e. adds an undirected edge */
+    pub fn insert_symmetric(&mut self, a: usize, b: usize) {
+        self.insert(a, b);
+        self.insert(b, a);
+    }
+
+    /** The words of row `r`, in `BitSet` layout, borrowed rather than copied */
+    pub fn row_words(&self, r: usize) -> &[u64] {
+        &self.words[r * self.words_per_row..(r + 1) * self.words_per_row]
+    }
+
+    fn row_mut(&mut self, r: usize) -> &mut [u64] {
+        &mut self.words[r * self.words_per_row..(r + 1) * self.words_per_row]
+    }
+
+    /** Iterates over the set columns of row `r`, i.e. the neighbours of vertex `r` */
+    pub fn row_iter(&self, r: usize) -> impl Iterator<Item = usize> + '_ {
+        BitSetIter::new(self.row_words(r)).map(|c| c as usize)
+    }
+
+    /** Row `r` as an owned set; prefer `row_words` in loops */
+    pub fn row_set(&self, r: usize) -> BitSet {
+        BitSet::from_words(self.row_words(r).to_vec())
+    }
+
+    /** Number of entries set in row `r`, i.e. the out-degree of vertex `r` */
+    pub fn row_count(&self, r: usize) -> usize {
+        count_words(self.row_words(r))
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn transpose(&self) -> BitMatrix {
+        let mut result = BitMatrix::new(self.n_cols, self.n_rows);
+        for r in 0..self.n_rows {
+            for c in self.row_iter(r) {
+                result.insert(c, r);
+            }
+        }
+        result
+    }
+
+    /** Boolean matrix product: (A * B)[i][j] is set if A[i][k] and B[k][j] for some k */
+    pub fn multiply(&self, other: &BitMatrix) -> BitMatrix {
+        assert_eq!(
+            self.n_cols, other.n_rows,
+            "Inner dimensions of BitMatrix product differ"
+        );
+        let mut result = BitMatrix::new(self.n_rows, other.n_cols);
+        for i in 0..self.n_rows {
+            for k in self.row_iter(i) {
+                for (target, val) in result.row_mut(i).iter_mut().zip(other.row_words(k)) {
+                    *target |= val;
+                }
+            }
+        }
+        result
+    }
+
+    /**
+     * Reachability matrix of a square matrix: entry (i, j) is set if there is a non-empty path
+     * from i to j. Uses Warshall's algorithm on whole rows.
+     */
+    pub fn transitive_closure(&self) -> BitMatrix {
+        assert_eq!(
+            self.n_rows, self.n_cols,
+            "Transitive closure needs a square BitMatrix"
+        );
+        let mut result = self.clone();
+        for k in 0..self.n_rows {
+            let row_k = result.row_words(k).to_vec();
+            for i in 0..self.n_rows {
+                if result.contains(i, k) {
+                    for (target, val) in result.row_mut(i).iter_mut().zip(row_k.iter()) {
+                        *target |= val;
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    /** Every triangle a < b < c of an undirected (symmetric) adjacency matrix */
+    pub fn triangles(&self) -> Vec<[usize; 3]> {
+        let mut result = Vec::new();
+        for a in 0..self.n_rows {
+            for b in self.row_iter(a).filter(|b| *b > a) {
+                let (row_a, row_b) = (self.row_words(a), self.row_words(b));
+                // common neighbours c > b, starting from the word holding b + 1
+                let first = (b + 1) / 64;
+                for idx in first..self.words_per_row {
+                    let mut common = row_a[idx] & row_b[idx];
+                    if idx == first {
+                        common &= u64::MAX << ((b + 1) % 64);
+                    }
+                    while common != 0 {
+                        result.push([a, b, idx * 64 + common.trailing_zeros() as usize]);
+                        common &= common - 1;
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    /** A largest clique of an undirected adjacency matrix, by Bron-Kerbosch with pivoting */
+    pub fn maximum_clique(&self) -> BitSet {
+        assert_eq!(
+            self.n_rows, self.n_cols,
+            "Clique search needs a square BitMatrix"
+        );
+        let mut candidates = vec![0; self.words_per_row];
+        for v in 0..self.n_rows {
+            candidates[v / 64] |= 1 << (v % 64);
+        }
+        let mut best = Vec::new();
+        let excluded = vec![0; self.words_per_row];
+        self.bron_kerbosch(&mut Vec::new(), candidates, excluded, &mut best);
+        best.into_iter().map(|v| v as u64).collect()
+    }
+
+    /**
+     * `p` and `x` are sets in `BitSet` word layout, combined with the borrowed rows directly
+     * so that the only allocations are the sets passed down to each branch
+     */
+    fn bron_kerbosch(
+        &self,
+        r: &mut Vec<usize>,
+        mut p: Vec<u64>,
+        mut x: Vec<u64>,
+        best: &mut Vec<usize>,
+    ) {
+        let p_len = count_words(&p);
+        if p_len == 0 && x.iter().all(|w| *w == 0) {
+            if r.len() > best.len() {
+                best.clone_from(r);
+            }
+            return;
+        }
+        // no clique through here can beat the best one
+        if r.len() + p_len <= best.len() {
+            return;
+        }
+
+        // only branch on vertices that are not neighbours of the pivot
+        let pivot = BitSetIter::new(&p)
+            .chain(BitSetIter::new(&x))
+            .max_by_key(|u| intersection_len_words(&p, self.row_words(*u as usize)))
+            .expect("p or x is non-empty");
+        let branches: Vec<u64> = p
+            .iter()
+            .zip(self.row_words(pivot as usize))
+            .map(|(a, b)| a & !b)
+            .collect();
+
+        for v in BitSetIter::new(&branches).map(|v| v as usize) {
+            let neighbours = self.row_words(v);
+            r.push(v);
+            self.bron_kerbosch(
+                r,
+                and_words(&p, neighbours),
+                and_words(&x, neighbours),
+                best,
+            );
+            r.pop();
+            p[v / 64] &= !(1 << (v % 64));
+            x[v / 64] |= 1 << (v % 64);
+        }
+    }
+}
+
+fn count_words(words: &[u64]) -> usize {
+    words.iter().map(|w| w.count_ones() as usize).sum()
+}
+
+fn intersection_len_words(a: &[u64], b: &[u64]) -> usize {
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| (x & y).count_ones() as usize)
+        .sum()
+}
+
+fn and_words(a: &[u64], b: &[u64]) -> Vec<u64> {
+    a.iter().zip(b).map(|(x, y)| x & y).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bit_set;
+
+    fn path_graph() -> BitMatrix {
+        // 0 -> 1 -> 2 -> 3
+        let mut m = BitMatrix::square(4);
+        m.insert(0, 1);
+        m.insert(1, 2);
+        m.insert(2, 3);
+        m
+    }
+
+    #[test]
+    fn test_transpose() {
+        let t = path_graph().transpose();
+        assert!(t.contains(1, 0));
+        assert!(!t.contains(0, 1));
+        assert_eq!(t.count_ones(), 3);
+    }
+
+    #[test]
+    fn test_multiply() {
+        let m = path_graph();
+        let two_steps = m.multiply(&m);
+        assert_eq!(two_steps.row_iter(0).collect::<Vec<_>>(), vec![2]);
+        assert_eq!(two_steps.row_iter(1).collect::<Vec<_>>(), vec![3]);
+        assert_eq!(two_steps.count_ones(), 2);
+    }
+
+    #[test]
+    fn test_transitive_closure() {
+        let mut m = path_graph();
+        let closure = m.transitive_closure();
+        assert_eq!(closure.row_iter(0).collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(closure.row_count(3), 0);
+
+        m.insert(3, 0);
+        let closure = m.transitive_closure();
+        assert_eq!(closure.count_ones(), 16);
+    }
+
+    #[test]
+    fn test_triangles_and_clique() {
+        let edges = [
+            ("a", "b"),
+            ("b", "c"),
+            ("a", "c"),
+            ("c", "d"),
+            ("b", "d"),
+            ("d", "e"),
+        ];
+        let (labels, m) = BitMatrix::from_undirected_edges(edges);
+        assert_eq!(labels, vec!["a", "b", "c", "d", "e"]);
+        assert_eq!(m.triangles(), vec![[0, 1, 2], [1, 2, 3]]);
+        assert_eq!(m.maximum_clique().len(), 3);
+
+        let mut m = m;
+        m.insert_symmetric(0, 3);
+        assert_eq!(m.maximum_clique(), bit_set!(0, 1, 2, 3));
+    }
+
+    #[test]
+    fn test_across_words() {
+        let mut m = BitMatrix::square(130);
+        for [a, b, c] in [[1, 70, 129], [63, 64, 65]] {
+            m.insert_symmetric(a, b);
+            m.insert_symmetric(b, c);
+            m.insert_symmetric(a, c);
+        }
+        for v in [1, 70, 129] {
+            m.insert_symmetric(v, 127);
+        }
+        m.insert_symmetric(64, 129);
+        assert_eq!(
+            m.triangles(),
+            vec![
+                [1, 70, 127],
+                [1, 70, 129],
+                [1, 127, 129],
+                [63, 64, 65],
+                [70, 127, 129]
+            ]
+        );
+        assert_eq!(m.maximum_clique(), bit_set!(1, 70, 127, 129));
+    }
+}
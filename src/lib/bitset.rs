@@ -1,9 +1,16 @@
-use std::fmt::Debug;
+use std::cmp::Ordering;
+use std::fmt::{self, Debug, Display};
+use std::hash::{Hash, Hasher};
+use std::ops::{
+    BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Bound, RangeBounds, Sub,
+    SubAssign,
+};
+use std::str::FromStr;
 
 /**
 * Stores a set of u64 values efficiently
 */
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 pub struct BitSet {
     vec: Vec<u64>,
     count: usize,
@@ -18,7 +25,7 @@ impl BitSet {
     }
 
     pub fn with_capacity(max_capacity: usize) -> Self {
-        let n_words = (max_capacity + 63) / 64; // ceil(max_capacity / 64)
+        let n_words = max_capacity.div_ceil(64);
         Self {
             vec: vec![0; n_words],
             count: 0,
@@ -79,6 +86,27 @@ impl BitSet {
         self.vec.len() * 64
     }
 
+    /** Drops trailing empty words, so the capacity only covers the largest value */
+    pub fn shrink_to_fit(&mut self) {
+        let n_words = self.significant_words().len();
+        self.vec.truncate(n_words);
+        self.vec.shrink_to_fit();
+    }
+
+    /** The backing words without trailing zero words, which don't affect the set's contents */
+    fn significant_words(&self) -> &[u64] {
+        trim_words(&self.vec)
+    }
+
+    pub(crate) fn words(&self) -> &[u64] {
+        &self.vec
+    }
+
+    pub(crate) fn from_words(vec: Vec<u64>) -> Self {
+        let count = vec.iter().map(|w| w.count_ones() as usize).sum();
+        Self { vec, count }
+    }
+
     pub fn intersection_inplace(&mut self, other: &BitSet) {
         for (target, val) in self.vec.iter_mut().zip(other.vec.iter()) {
             let num_to_remove = (*target & !*val).count_ones();
@@ -128,13 +156,409 @@ impl BitSet {
         cl
     }
 
+    pub fn symmetric_difference_inplace(&mut self, other: &BitSet) {
+        self.extend_capacity(other.vec.len());
+
+        for (target, val) in self.vec.iter_mut().zip(other.vec.iter()) {
+            let num_removed = (*target & *val).count_ones() as usize;
+            let num_added = (!*target & *val).count_ones() as usize;
+            *target ^= val;
+            self.count = self.count + num_added - num_removed;
+        }
+    }
+
+    pub fn symmetric_difference(&self, other: &BitSet) -> BitSet {
+        let mut cl = self.clone();
+        cl.symmetric_difference_inplace(other);
+        cl
+    }
+
+    /** Size of the intersection, without building it */
+    pub fn intersection_len(&self, other: &BitSet) -> usize {
+        self.vec
+            .iter()
+            .zip(other.vec.iter())
+            .map(|(a, b)| (a & b).count_ones() as usize)
+            .sum()
+    }
+
+    /** Size of the union, without building it */
+    pub fn union_len(&self, other: &BitSet) -> usize {
+        self.len() + other.len() - self.intersection_len(other)
+    }
+
+    /** Size of the difference, without building it */
+    pub fn difference_len(&self, other: &BitSet) -> usize {
+        self.len() - self.intersection_len(other)
+    }
+
+    /** Size of the symmetric difference, without building it */
+    pub fn symmetric_difference_len(&self, other: &BitSet) -> usize {
+        self.len() + other.len() - 2 * self.intersection_len(other)
+    }
+
+    /** true if every value of this set is also in `other` */
+    pub fn is_subset(&self, other: &BitSet) -> bool {
+        self.len() <= other.len() && self.intersection_len(other) == self.len()
+    }
+
+    /** true if every value of `other` is also in this set */
+    pub fn is_superset(&self, other: &BitSet) -> bool {
+        other.is_subset(self)
+    }
+
+    /** true if the two sets share no values */
+    pub fn is_disjoint(&self, other: &BitSet) -> bool {
+        self.vec
+            .iter()
+            .zip(other.vec.iter())
+            .all(|(a, b)| a & b == 0)
+    }
+
     pub fn iter(&self) -> BitSetIter<'_> {
+        BitSetIter::new(&self.vec)
+    }
+
+    /** Smallest value in the set. Named like `BTreeSet::first`, since `Ord::min` is taken */
+    pub fn first(&self) -> Option<u64> {
+        self.iter().next()
+    }
+
+    /** Largest value in the set. Named like `BTreeSet::last`, since `Ord::max` is taken */
+    pub fn last(&self) -> Option<u64> {
+        self.vec
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, w)| **w != 0)
+            .map(|(i, w)| 64 * (i as u64) + 63 - w.leading_zeros() as u64)
+    }
+
+    /** Smallest value in the set strictly greater than `n` */
+    pub fn next_set_after(&self, n: u64) -> Option<u64> {
+        self.iter_from(n.checked_add(1)?).next()
+    }
+
+    /** Iterates over the values that are at least `start` */
+    fn iter_from(&self, start: u64) -> BitSetIter<'_> {
+        let (word, idx) = ((start / 64) as usize, start % 64);
         BitSetIter {
-            current_idx: 0,
-            current_word: 0,
             data: &self.vec,
+            word_idx: word,
+            current: self.vec.get(word).map_or(0, |w| w & (u64::MAX << idx)),
         }
     }
+
+    /** Number of values in the set strictly less than `n` */
+    pub fn rank(&self, n: u64) -> usize {
+        let (word, idx) = ((n / 64) as usize, n % 64);
+        let full_words: usize = self
+            .vec
+            .iter()
+            .take(word)
+            .map(|w| w.count_ones() as usize)
+            .sum();
+        let partial = self
+            .vec
+            .get(word)
+            .map_or(0, |w| (w & ((1u64 << idx) - 1)).count_ones() as usize);
+        full_words + partial
+    }
+
+    /** The `k`th smallest value in the set, counting from 0 */
+    pub fn select(&self, k: usize) -> Option<u64> {
+        if k >= self.count {
+            return None;
+        }
+        let mut remaining = k as u32;
+        for (i, w) in self.vec.iter().enumerate() {
+            let ones = w.count_ones();
+            if remaining < ones {
+                // clear the lowest `remaining` bits, then the answer is the lowest bit left
+                let mut w = *w;
+                for _ in 0..remaining {
+                    w &= w - 1;
+                }
+                return Some(64 * (i as u64) + w.trailing_zeros() as u64);
+            }
+            remaining -= ones;
+        }
+        None
+    }
+}
+
+/**
+* Converts a range of values to half-open bounds `[start, end)`, using `unbounded_end` if the
+* range has no upper bound
+*/
+fn range_bounds<R: RangeBounds<u64>>(range: &R, unbounded_end: u64) -> (u64, u64) {
+    let start = match range.start_bound() {
+        Bound::Included(s) => *s,
+        Bound::Excluded(s) => s.saturating_add(1),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        // no set holds u64::MAX, so `..=u64::MAX` is clamped to "everything before it"
+        Bound::Included(e) => e.saturating_add(1),
+        Bound::Excluded(e) => *e,
+        Bound::Unbounded => unbounded_end,
+    };
+    (start, end.max(start))
+}
+
+/** The words covered by the values `[start, end)`, with a mask of the covered bits in each */
+fn range_masks(start: u64, end: u64) -> impl Iterator<Item = (usize, u64)> {
+    let last = end.saturating_sub(1);
+    let n_words = if start < end {
+        last / 64 - start / 64 + 1
+    } else {
+        0
+    };
+    let words = (0..n_words).map(move |i| start / 64 + i);
+    words.map(move |w| {
+        let lo = if w == start / 64 { start % 64 } else { 0 };
+        let hi = if w == last / 64 { last % 64 } else { 63 };
+        (w as usize, (u64::MAX >> (63 - hi)) & (u64::MAX << lo))
+    })
+}
+
+impl BitSet {
+    /** The set containing every value in `0..n` */
+    pub fn full(n: u64) -> Self {
+        let mut set = BitSet::new();
+        set.insert_range(0..n);
+        set
+    }
+
+    /** Inserts every value in the range, a word at a time */
+    pub fn insert_range<R: RangeBounds<u64>>(&mut self, range: R) {
+        assert!(
+            !matches!(range.end_bound(), Bound::Unbounded),
+            "insert_range needs an upper bound"
+        );
+        let (start, end) = range_bounds(&range, 0);
+        if start == end {
+            return;
+        }
+        self.extend_capacity(((end - 1) / 64 + 1) as usize);
+        for (w, mask) in range_masks(start, end) {
+            self.count += (mask & !self.vec[w]).count_ones() as usize;
+            self.vec[w] |= mask;
+        }
+    }
+
+    /** Removes every value in the range, a word at a time */
+    pub fn remove_range<R: RangeBounds<u64>>(&mut self, range: R) {
+        let (start, end) = range_bounds(&range, self.capacity() as u64);
+        let end = end.min(self.capacity() as u64);
+        for (w, mask) in range_masks(start, end.max(start)) {
+            self.count -= (mask & self.vec[w]).count_ones() as usize;
+            self.vec[w] &= !mask;
+        }
+    }
+
+    /**
+     * true if every value in the range is in the set. Always true for an empty range.
+     * Unlike the other range operations, an open-ended range or one reaching past the
+     * capacity is not clamped: it is always false, even if the set holds every value up to
+     * its capacity, since the values past the capacity are not in the set.
+     */
+    pub fn contains_range<R: RangeBounds<u64>>(&self, range: R) -> bool {
+        if matches!(range.end_bound(), Bound::Unbounded) {
+            return false;
+        }
+        let (start, end) = range_bounds(&range, self.capacity() as u64);
+        range_masks(start, end).all(|(w, mask)| self.vec.get(w).is_some_and(|v| v & mask == mask))
+    }
+
+    /** Number of values of the set in the range */
+    pub fn count_range<R: RangeBounds<u64>>(&self, range: R) -> usize {
+        let (start, end) = range_bounds(&range, self.capacity() as u64);
+        let end = end.min(self.capacity() as u64);
+        range_masks(start, end.max(start))
+            .map(|(w, mask)| (self.vec[w] & mask).count_ones() as usize)
+            .sum()
+    }
+
+    /** Iterates over the values of the set in the range, in increasing order */
+    pub fn iter_range<R: RangeBounds<u64>>(&self, range: R) -> impl Iterator<Item = u64> + '_ {
+        let (start, end) = range_bounds(&range, u64::MAX);
+        let unbounded = matches!(range.end_bound(), Bound::Unbounded);
+        self.iter_from(start)
+            .take_while(move |v| unbounded || *v < end)
+    }
+}
+
+impl BitSet {
+    /**
+     * Little-endian bytes, where bit `j` of byte `i` is the value `8 * i + j`.
+     * Trailing zero bytes are dropped.
+     */
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = self
+            .significant_words()
+            .iter()
+            .flat_map(|w| w.to_le_bytes())
+            .collect();
+        while bytes.last() == Some(&0) {
+            bytes.pop();
+        }
+        bytes
+    }
+
+    /** Inverse of `to_bytes` */
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        let words = bytes
+            .chunks(8)
+            .map(|chunk| {
+                let mut word = [0u8; 8];
+                word[..chunk.len()].copy_from_slice(chunk);
+                u64::from_le_bytes(word)
+            })
+            .collect();
+        Self::from_words(words)
+    }
+
+    /** The bytes of `to_bytes` as lowercase hex, two digits per byte */
+    pub fn to_hex(&self) -> String {
+        self.to_bytes()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
+    /** Inverse of `to_hex` */
+    pub fn from_hex(s: &str) -> Result<Self, ParseBitSetError> {
+        if !s.len().is_multiple_of(2) {
+            return Err(ParseBitSetError(format!(
+                "odd number of hex digits in {:?}",
+                s
+            )));
+        }
+        let bytes = (0..s.len())
+            .step_by(2)
+            .map(|i| {
+                // from_str_radix alone would accept a sign, as in "+f"
+                s.get(i..i + 2)
+                    .filter(|byte| byte.bytes().all(|b| b.is_ascii_hexdigit()))
+                    .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                    .ok_or_else(|| ParseBitSetError(format!("invalid hex in {:?}", s)))
+            })
+            .collect::<Result<Vec<u8>, _>>()?;
+        Ok(Self::from_bytes(&bytes))
+    }
+}
+
+/**
+* Operations shared by the bit set types, so algorithms can be written once for all of them
+*/
+pub trait BitSetOps: Clone {
+    type Iter<'a>: Iterator<Item = u64>
+    where
+        Self: 'a;
+
+    fn insert(&mut self, value: u64);
+    fn remove(&mut self, value: u64);
+    fn contains(&self, value: u64) -> bool;
+    fn len(&self) -> usize;
+    fn iter(&self) -> Self::Iter<'_>;
+
+    fn union_inplace(&mut self, other: &Self);
+    fn intersection_inplace(&mut self, other: &Self);
+    fn difference_inplace(&mut self, other: &Self);
+    fn symmetric_difference_inplace(&mut self, other: &Self);
+
+    fn intersection_len(&self, other: &Self) -> usize;
+    fn is_disjoint(&self, other: &Self) -> bool;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /** Smallest value in the set */
+    fn first(&self) -> Option<u64> {
+        self.iter().next()
+    }
+
+    fn union(&self, other: &Self) -> Self {
+        let mut c = self.clone();
+        c.union_inplace(other);
+        c
+    }
+
+    fn intersection(&self, other: &Self) -> Self {
+        let mut c = self.clone();
+        c.intersection_inplace(other);
+        c
+    }
+
+    fn difference(&self, other: &Self) -> Self {
+        let mut c = self.clone();
+        c.difference_inplace(other);
+        c
+    }
+
+    fn symmetric_difference(&self, other: &Self) -> Self {
+        let mut c = self.clone();
+        c.symmetric_difference_inplace(other);
+        c
+    }
+
+    fn is_subset(&self, other: &Self) -> bool {
+        self.len() <= other.len() && self.intersection_len(other) == self.len()
+    }
+
+    fn is_superset(&self, other: &Self) -> bool {
+        other.is_subset(self)
+    }
+}
+
+impl BitSetOps for BitSet {
+    type Iter<'a> = BitSetIter<'a>;
+
+    fn insert(&mut self, value: u64) {
+        BitSet::insert(self, value)
+    }
+
+    fn remove(&mut self, value: u64) {
+        BitSet::remove(self, value)
+    }
+
+    fn contains(&self, value: u64) -> bool {
+        BitSet::contains(self, value)
+    }
+
+    fn len(&self) -> usize {
+        BitSet::len(self)
+    }
+
+    fn iter(&self) -> BitSetIter<'_> {
+        BitSet::iter(self)
+    }
+
+    fn union_inplace(&mut self, other: &Self) {
+        BitSet::union_inplace(self, other)
+    }
+
+    fn intersection_inplace(&mut self, other: &Self) {
+        BitSet::intersection_inplace(self, other)
+    }
+
+    fn difference_inplace(&mut self, other: &Self) {
+        BitSet::difference_inplace(self, other)
+    }
+
+    fn symmetric_difference_inplace(&mut self, other: &Self) {
+        BitSet::symmetric_difference_inplace(self, other)
+    }
+
+    fn intersection_len(&self, other: &Self) -> usize {
+        BitSet::intersection_len(self, other)
+    }
+
+    fn is_disjoint(&self, other: &Self) -> bool {
+        BitSet::is_disjoint(self, other)
+    }
 }
 
 impl std::default::Default for BitSet {
@@ -143,47 +567,250 @@ impl std::default::Default for BitSet {
     }
 }
 
+macro_rules! impl_set_op {
+    ($op:ident, $method:ident, $assign_op:ident, $assign_method:ident, $inplace:ident) => {
+        impl $assign_op<&BitSet> for BitSet {
+            fn $assign_method(&mut self, rhs: &BitSet) {
+                self.$inplace(rhs);
+            }
+        }
+
+        impl $assign_op<BitSet> for BitSet {
+            fn $assign_method(&mut self, rhs: BitSet) {
+                self.$inplace(&rhs);
+            }
+        }
+
+        impl $op<&BitSet> for &BitSet {
+            type Output = BitSet;
+
+            fn $method(self, rhs: &BitSet) -> BitSet {
+                let mut result = self.clone();
+                result.$inplace(rhs);
+                result
+            }
+        }
+
+        impl $op<BitSet> for &BitSet {
+            type Output = BitSet;
+
+            fn $method(self, rhs: BitSet) -> BitSet {
+                self.$method(&rhs)
+            }
+        }
+
+        impl $op<&BitSet> for BitSet {
+            type Output = BitSet;
+
+            fn $method(mut self, rhs: &BitSet) -> BitSet {
+                self.$inplace(rhs);
+                self
+            }
+        }
+
+        impl $op<BitSet> for BitSet {
+            type Output = BitSet;
+
+            fn $method(mut self, rhs: BitSet) -> BitSet {
+                self.$inplace(&rhs);
+                self
+            }
+        }
+    };
+}
+
+impl_set_op!(
+    BitAnd,
+    bitand,
+    BitAndAssign,
+    bitand_assign,
+    intersection_inplace
+);
+impl_set_op!(BitOr, bitor, BitOrAssign, bitor_assign, union_inplace);
+impl_set_op!(
+    BitXor,
+    bitxor,
+    BitXorAssign,
+    bitxor_assign,
+    symmetric_difference_inplace
+);
+impl_set_op!(Sub, sub, SubAssign, sub_assign, difference_inplace);
+
+/** Error from parsing a `BitSet` from hex or from its `Display` form */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseBitSetError(pub String);
+
+impl Display for ParseBitSetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Could not parse bit set: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseBitSetError {}
+
+/** Writes values as `{1, 4..=9}`, compressing runs of three or more values into ranges */
+pub(crate) fn fmt_values<I: Iterator<Item = u64>>(
+    values: I,
+    f: &mut fmt::Formatter<'_>,
+) -> fmt::Result {
+    let mut runs: Vec<(u64, u64)> = Vec::new();
+    for value in values {
+        match runs.last_mut() {
+            Some((_, end)) if end.checked_add(1) == Some(value) => *end = value,
+            _ => runs.push((value, value)),
+        }
+    }
+
+    write!(f, "{{")?;
+    for (i, (start, end)) in runs.into_iter().enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+        match end - start {
+            0 => write!(f, "{}", start)?,
+            1 => write!(f, "{}, {}", start, end)?,
+            _ => write!(f, "{}..={}", start, end)?,
+        }
+    }
+    write!(f, "}}")
+}
+
+impl Display for BitSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_values(self.iter(), f)
+    }
+}
+
+impl Debug for BitSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "BitSet")?;
+        fmt_values(self.iter(), f)
+    }
+}
+
+/** Parses the `Display` form, e.g. `{1, 4..=9}` */
+impl FromStr for BitSet {
+    type Err = ParseBitSetError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |part: &str| ParseBitSetError(format!("invalid value {:?}", part));
+        let inner = s
+            .trim()
+            .strip_prefix('{')
+            .and_then(|rest| rest.strip_suffix('}'))
+            .ok_or_else(|| ParseBitSetError(format!("expected braces around {:?}", s)))?;
+
+        let mut set = BitSet::new();
+        for part in inner.split(',').map(|p| p.trim()).filter(|p| !p.is_empty()) {
+            match part.split_once("..=") {
+                Some((start, end)) => {
+                    let start: u64 = start.trim().parse().map_err(|_| invalid(part))?;
+                    let end: u64 = end.trim().parse().map_err(|_| invalid(part))?;
+                    if start > end {
+                        return Err(ParseBitSetError(format!("empty range {:?}", part)));
+                    }
+                    set.insert_range(start..=end);
+                }
+                None => set.insert(part.parse().map_err(|_| invalid(part))?),
+            }
+        }
+        Ok(set)
+    }
+}
+
 impl PartialEq for BitSet {
     fn eq(&self, other: &Self) -> bool {
-        self.len() == other.len() && self.vec.iter().zip(other.vec.iter()).all(|(a, b)| *a == *b)
+        self.len() == other.len() && self.significant_words() == other.significant_words()
     }
 }
 
 impl Eq for BitSet {}
 
+impl Hash for BitSet {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.significant_words().hash(state);
+    }
+}
+
+/**
+* Orders sets by their values in increasing order, the same way as `BTreeSet<u64>`
+*/
+impl Ord for BitSet {
+    fn cmp(&self, other: &Self) -> Ordering {
+        cmp_words(&self.vec, &other.vec)
+    }
+}
+
+impl PartialOrd for BitSet {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/** Strips trailing zero words, which don't affect a set's contents */
+pub(crate) fn trim_words(words: &[u64]) -> &[u64] {
+    let n_words = words.iter().rposition(|w| *w != 0).map_or(0, |idx| idx + 1);
+    &words[..n_words]
+}
+
+/** Compares two word slices as sets, in the same order as `BTreeSet<u64>` */
+pub(crate) fn cmp_words(a: &[u64], b: &[u64]) -> Ordering {
+    let (a, b) = (trim_words(a), trim_words(b));
+    for i in 0..a.len().max(b.len()) {
+        let x = a.get(i).copied().unwrap_or(0);
+        let y = b.get(i).copied().unwrap_or(0);
+        if x == y {
+            continue;
+        }
+        // the smallest value in exactly one of the sets decides the order
+        let low = (x ^ y).trailing_zeros();
+        let (other_words, ordering) = if (x >> low) & 1 == 1 {
+            (b, Ordering::Less)
+        } else {
+            (a, Ordering::Greater)
+        };
+        // the set without that value is smaller if it has nothing larger to compare against
+        let other_word = other_words.get(i).copied().unwrap_or(0);
+        let other_has_larger =
+            (low < 63 && other_word >> (low + 1) != 0) || other_words.len() > i + 1;
+        return if other_has_larger {
+            ordering
+        } else {
+            ordering.reverse()
+        };
+    }
+    Ordering::Equal
+}
+
 pub struct BitSetIter<'a> {
     data: &'a [u64],
-    current_word: usize,
-    current_idx: u64,
+    word_idx: usize,
+    /** bits of `data[word_idx]` that have not been yielded yet */
+    current: u64,
+}
+
+impl<'a> BitSetIter<'a> {
+    pub(crate) fn new(data: &'a [u64]) -> Self {
+        Self {
+            data,
+            word_idx: 0,
+            current: data.first().copied().unwrap_or(0),
+        }
+    }
 }
 
 impl Iterator for BitSetIter<'_> {
     type Item = u64;
 
     fn next(&mut self) -> Option<Self::Item> {
-        while self.current_word < self.data.len() {
-            let chunk = self.data[self.current_word];
-
-            if chunk == 0 {
-                self.current_word += 1;
-                self.current_idx = 0;
-                continue;
-            }
-
-            while self.current_idx < 64 {
-                if (chunk & (1 << self.current_idx)) > 0 {
-                    let result = 64 * (self.current_word as u64) + self.current_idx;
-                    self.current_idx += 1;
-                    return Some(result);
-                }
-                self.current_idx += 1;
-            }
-
-            self.current_word += 1;
-            self.current_idx = 0;
+        while self.current == 0 {
+            self.word_idx += 1;
+            self.current = *self.data.get(self.word_idx)?;
         }
-
-        None
+        let idx = self.current.trailing_zeros() as u64;
+        // clear the lowest set bit
+        self.current &= self.current - 1;
+        Some(64 * (self.word_idx as u64) + idx)
     }
 }
 
@@ -294,4 +921,241 @@ mod tests {
         let act: Vec<u64> = v.iter().collect();
         assert_eq!(x, act);
     }
+
+    #[test]
+    fn test_iter_empty() {
+        assert_eq!(BitSet::new().iter().next(), None);
+        assert_eq!(BitSet::with_capacity(200).iter().next(), None);
+    }
+
+    #[test]
+    fn test_first_last() {
+        let v = bit_set!(5, 64, 300);
+        assert_eq!(v.first(), Some(5));
+        assert_eq!(v.last(), Some(300));
+
+        let mut v = BitSet::with_capacity(500);
+        assert_eq!(v.first(), None);
+        assert_eq!(v.last(), None);
+        v.insert(63);
+        assert_eq!(v.last(), Some(63));
+    }
+
+    #[test]
+    fn test_next_set_after() {
+        let v = bit_set!(1, 63, 64, 200);
+        assert_eq!(v.next_set_after(0), Some(1));
+        assert_eq!(v.next_set_after(1), Some(63));
+        assert_eq!(v.next_set_after(63), Some(64));
+        assert_eq!(v.next_set_after(64), Some(200));
+        assert_eq!(v.next_set_after(200), None);
+        assert_eq!(v.next_set_after(u64::MAX), None);
+    }
+
+    #[test]
+    fn test_rank_select() {
+        let v = bit_set!(1, 63, 64, 200);
+        assert_eq!(v.rank(0), 0);
+        assert_eq!(v.rank(2), 1);
+        assert_eq!(v.rank(64), 2);
+        assert_eq!(v.rank(65), 3);
+        assert_eq!(v.rank(10_000), 4);
+
+        let selected: Vec<_> = (0..5).map(|k| v.select(k)).collect();
+        assert_eq!(selected, vec![Some(1), Some(63), Some(64), Some(200), None]);
+        for (k, x) in v.iter().enumerate() {
+            assert_eq!(v.rank(x), k);
+        }
+    }
+
+    #[test]
+    fn test_symmetric_difference() {
+        let mut v = bit_set!(1, 4, 5);
+        v.symmetric_difference_inplace(&bit_set!(2, 4, 600));
+        assert_eq!(v.len(), 4);
+        assert_eq!(v, bit_set!(1, 2, 5, 600));
+    }
+
+    #[test]
+    fn test_operators() {
+        let a = bit_set!(1, 4, 5, 100);
+        let b = bit_set!(4, 5, 6);
+        assert_eq!(&a & &b, bit_set!(4, 5));
+        assert_eq!(&a | &b, bit_set!(1, 4, 5, 6, 100));
+        assert_eq!(&a ^ &b, bit_set!(1, 6, 100));
+        assert_eq!(&a - &b, bit_set!(1, 100));
+        assert_eq!(a.clone() - b.clone(), bit_set!(1, 100));
+
+        let mut c = a.clone();
+        c |= &b;
+        c -= bit_set!(1);
+        c &= &bit_set!(4, 6, 100, 101);
+        c ^= &bit_set!(6, 7);
+        assert_eq!(c, bit_set!(4, 7, 100));
+    }
+
+    #[test]
+    fn test_predicates_and_lengths() {
+        let a = bit_set!(1, 4, 5, 100);
+        let b = bit_set!(4, 5);
+        let c = bit_set!(2, 200);
+        assert!(b.is_subset(&a));
+        assert!(!a.is_subset(&b));
+        assert!(a.is_superset(&b));
+        assert!(a.is_disjoint(&c));
+        assert!(!a.is_disjoint(&b));
+        assert!(BitSet::new().is_subset(&c));
+
+        assert_eq!(a.intersection_len(&b), 2);
+        assert_eq!(a.union_len(&c), 6);
+        assert_eq!(a.difference_len(&b), 2);
+        assert_eq!(a.symmetric_difference_len(&bit_set!(5, 6)), 4);
+    }
+
+    #[test]
+    fn test_hash_ignores_trailing_words() {
+        use std::collections::HashSet;
+        let a = BitSet {
+            vec: vec![0b10101, 0],
+            count: 3,
+        };
+        let b = BitSet {
+            vec: vec![0b10101],
+            count: 3,
+        };
+        let set: HashSet<BitSet> = [a, b].into_iter().collect();
+        assert_eq!(set.len(), 1);
+    }
+
+    #[test]
+    fn test_ord_matches_btreeset() {
+        use std::collections::BTreeSet;
+        let sets: Vec<Vec<u64>> = vec![
+            vec![],
+            vec![0],
+            vec![1],
+            vec![0, 1],
+            vec![0, 64],
+            vec![1, 64],
+            vec![63],
+            vec![63, 64],
+            vec![64],
+            vec![2, 3, 200],
+            vec![2, 3],
+            vec![2, 4],
+            vec![200],
+        ];
+        for a in sets.iter() {
+            for b in sets.iter() {
+                let sa: BitSet = a.iter().copied().collect();
+                let mut sb: BitSet = b.iter().copied().collect();
+                sb.union_inplace(&BitSet::with_capacity(1000));
+                let ta: BTreeSet<u64> = a.iter().copied().collect();
+                let tb: BTreeSet<u64> = b.iter().copied().collect();
+                assert_eq!(sa.cmp(&sb), ta.cmp(&tb), "{:?} vs {:?}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_shrink_to_fit() {
+        let mut v = BitSet::with_capacity(1000);
+        v.insert(70);
+        v.shrink_to_fit();
+        assert_eq!(v.capacity(), 128);
+        assert_eq!(v, bit_set!(70));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(bit_set!(1, 4, 5, 6, 7, 8, 9).to_string(), "{1, 4..=9}");
+        assert_eq!(
+            bit_set!(1, 2, 4, 63, 64, 65).to_string(),
+            "{1, 2, 4, 63..=65}"
+        );
+        assert_eq!(BitSet::new().to_string(), "{}");
+        assert_eq!(format!("{:?}", bit_set!(3)), "BitSet{3}");
+    }
+
+    #[test]
+    fn test_parse_display() {
+        let v = bit_set!(1, 2, 4, 63, 64, 65, 300);
+        assert_eq!(v.to_string().parse::<BitSet>(), Ok(v));
+        assert_eq!("{ 5..=7 ,9}".parse::<BitSet>(), Ok(bit_set!(5, 6, 7, 9)));
+        assert!("{1, x}".parse::<BitSet>().is_err());
+        assert!("1, 2".parse::<BitSet>().is_err());
+        assert!("{5..=3}".parse::<BitSet>().is_err());
+        assert_eq!("{0..=99999}".parse::<BitSet>().map(|v| v.len()), Ok(100000));
+    }
+
+    #[test]
+    fn test_bytes_and_hex() {
+        let v = bit_set!(1, 8, 70);
+        assert_eq!(v.to_bytes(), vec![0b10, 1, 0, 0, 0, 0, 0, 0, 0b1000000]);
+        assert_eq!(BitSet::from_bytes(&v.to_bytes()), v);
+        assert_eq!(v.to_hex(), "020100000000000040");
+        assert_eq!(BitSet::from_hex(&v.to_hex()), Ok(v));
+        assert_eq!(BitSet::with_capacity(200).to_bytes(), Vec::<u8>::new());
+        assert!(BitSet::from_hex("0").is_err());
+        assert!(BitSet::from_hex("zz").is_err());
+        assert!(BitSet::from_hex("+f").is_err());
+    }
+
+    #[test]
+    fn test_full() {
+        assert_eq!(BitSet::full(130), (0..130).collect());
+        assert_eq!(BitSet::full(130).len(), 130);
+        assert_eq!(BitSet::full(0), BitSet::new());
+    }
+
+    #[test]
+    fn test_insert_remove_range() {
+        let mut v = bit_set!(3, 500);
+        v.insert_range(60..=130);
+        assert_eq!(v, (60..=130).chain([3, 500]).collect());
+        assert_eq!(v.len(), 73);
+
+        v.remove_range(64..128);
+        assert_eq!(v, (60..64).chain(128..=130).chain([3, 500]).collect());
+        assert_eq!(v.len(), 9);
+
+        v.remove_range(100..);
+        assert_eq!(v, bit_set!(3, 60, 61, 62, 63));
+        v.remove_range(..=60);
+        assert_eq!(v, bit_set!(61, 62, 63));
+        v.insert_range(10..10);
+        assert_eq!(v.len(), 3);
+    }
+
+    #[test]
+    fn test_range_queries() {
+        let v: BitSet = (60..=130).chain([3, 500]).collect();
+        assert!(v.contains_range(60..131));
+        assert!(!v.contains_range(59..131));
+        assert!(!v.contains_range(60..132));
+        assert!(v.contains_range(1000..1000));
+        assert!(!v.contains_range(1000..1001));
+        assert!(!bit_set!(5, 6, 7).contains_range(5..));
+        assert!(bit_set!(0, 1, 2).contains_range(..=2));
+
+        assert_eq!(v.count_range(..), 73);
+        assert_eq!(v.count_range(0..64), 5);
+        assert_eq!(v.count_range(129..10_000), 3);
+
+        assert_eq!(
+            v.iter_range(125..).collect::<Vec<_>>(),
+            vec![125, 126, 127, 128, 129, 130, 500]
+        );
+        assert_eq!(v.iter_range(..62).collect::<Vec<_>>(), vec![3, 60, 61]);
+        assert_eq!(v.iter_range(131..500).count(), 0);
+
+        // an inclusive end of u64::MAX runs to the end of the set
+        assert_eq!(v.count_range(2..=u64::MAX), 73);
+        assert_eq!(v.iter_range(131..=u64::MAX).collect::<Vec<_>>(), vec![500]);
+        assert!(!BitSet::full(64).contains_range(0..=u64::MAX));
+        assert!(!BitSet::full(64).contains_range(0..));
+        let mut w = v.clone();
+        w.remove_range(4..=u64::MAX);
+        assert_eq!(w, bit_set!(3));
+    }
 }
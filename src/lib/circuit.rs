@@ -0,0 +1,506 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+/** An interned wire name, only meaningful for the `Netlist` that created it */
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct WireId(u32);
+
+impl WireId {
+    pub fn index(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum GateOp {
+    And,
+    Or,
+    Xor,
+}
+
+impl GateOp {
+    pub fn apply(&self, lhs: bool, rhs: bool) -> bool {
+        match self {
+            GateOp::And => lhs & rhs,
+            GateOp::Or => lhs | rhs,
+            GateOp::Xor => lhs ^ rhs,
+        }
+    }
+}
+
+impl FromStr for GateOp {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "AND" => Ok(GateOp::And),
+            "OR" => Ok(GateOp::Or),
+            "XOR" => Ok(GateOp::Xor),
+            _ => Err(s.to_string()),
+        }
+    }
+}
+
+impl Display for GateOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            GateOp::And => "AND",
+            GateOp::Or => "OR",
+            GateOp::Xor => "XOR",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Gate {
+    pub inputs: [WireId; 2],
+    pub op: GateOp,
+    pub output: WireId,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CircuitError {
+    /** A line that is neither `wire: value` nor `a OP b -> c` */
+    Parse {
+        line: usize,
+        message: String,
+    },
+    UnknownOperator {
+        line: usize,
+        op: String,
+    },
+    /** A wire that is the output of more than one gate or input */
+    MultipleDrivers(String),
+    /** Wires that are read but never given a value */
+    Undriven(Vec<String>),
+    /** Wires that form a cycle of gates, in order around the cycle */
+    Loop(Vec<String>),
+    /** A bus with more bits than fit in a u64 */
+    BusTooWide {
+        prefix: String,
+        width: usize,
+    },
+}
+
+impl Display for CircuitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CircuitError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+            CircuitError::UnknownOperator { line, op } => {
+                write!(f, "line {}: unknown operator `{}`", line, op)
+            }
+            CircuitError::MultipleDrivers(wire) => write!(f, "{} has more than one driver", wire),
+            CircuitError::Undriven(wires) => write!(f, "undriven wires: {}", wires.join(", ")),
+            CircuitError::Loop(wires) => write!(f, "combinational loop: {}", wires.join(" -> ")),
+            CircuitError::BusTooWide { prefix, width } => {
+                write!(
+                    f,
+                    "bus {} has {} bits, at most 64 are supported",
+                    prefix, width
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for CircuitError {}
+
+/**
+* A circuit of two-input gates between named wires, plus fixed values for its input wires.
+* Wire names are interned, so gates refer to wires by `WireId`.
+*/
+#[derive(Clone, Debug, Default)]
+pub struct Netlist {
+    names: Vec<String>,
+    ids: HashMap<String, WireId>,
+    gates: Vec<Gate>,
+    /** index into `gates` of the gate driving each wire */
+    drivers: HashMap<WireId, usize>,
+    inputs: HashMap<WireId, bool>,
+}
+
+impl Netlist {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /**
+     * Parses the day 24 format: `name: 0|1` lines for the inputs, then a blank line,
+     * then one `a OP b -> c` line per gate.
+     */
+    pub fn parse(input: &str) -> Result<Self, CircuitError> {
+        let mut netlist = Netlist::new();
+        for (idx, line) in input.lines().enumerate() {
+            let line = line.trim();
+            let parse_error = |message: &str| CircuitError::Parse {
+                line: idx + 1,
+                message: message.to_string(),
+            };
+            if line.is_empty() {
+                continue;
+            }
+            if let Some((name, value)) = line.split_once(": ") {
+                let value = match value {
+                    "0" => false,
+                    "1" => true,
+                    _ => return Err(parse_error("input value is not 0 or 1")),
+                };
+                let wire = netlist.wire(name);
+                netlist.set_input(wire, value)?;
+            } else {
+                let (lhs, output) = line
+                    .split_once(" -> ")
+                    .ok_or_else(|| parse_error("expected `a OP b -> c`"))?;
+                let [a, op, b] = lhs.split_whitespace().collect::<Vec<_>>()[..] else {
+                    return Err(parse_error("expected `a OP b -> c`"));
+                };
+                let op = op
+                    .parse()
+                    .map_err(|op| CircuitError::UnknownOperator { line: idx + 1, op })?;
+                let (a, b, output) = (netlist.wire(a), netlist.wire(b), netlist.wire(output));
+                netlist.add_gate(a, op, b, output)?;
+            }
+        }
+        Ok(netlist)
+    }
+
+    /** The id of the wire with this name, creating it if needed */
+    pub fn wire(&mut self, name: &str) -> WireId {
+        if let Some(id) = self.ids.get(name) {
+            return *id;
+        }
+        let id = WireId(self.names.len() as u32);
+        self.names.push(name.to_string());
+        self.ids.insert(name.to_string(), id);
+        id
+    }
+
+    /** The id of an existing wire */
+    pub fn id(&self, name: &str) -> Option<WireId> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, wire: WireId) -> &str {
+        &self.names[wire.index()]
+    }
+
+    pub fn n_wires(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn gates(&self) -> &[Gate] {
+        &self.gates
+    }
+
+    /** The gate whose output is `wire` */
+    pub fn driver(&self, wire: WireId) -> Option<&Gate> {
+        self.drivers.get(&wire).map(|idx| &self.gates[*idx])
+    }
+
+    /** The gates that read `wire` */
+    pub fn readers(&self, wire: WireId) -> impl Iterator<Item = &Gate> {
+        self.gates.iter().filter(move |g| g.inputs.contains(&wire))
+    }
+
+    /** The gate computing `a op b`, with the inputs in either order */
+    pub fn find_gate(&self, a: WireId, op: GateOp, b: WireId) -> Option<&Gate> {
+        self.gates
+            .iter()
+            .find(|g| g.op == op && (g.inputs == [a, b] || g.inputs == [b, a]))
+    }
+
+    pub fn add_gate(
+        &mut self,
+        a: WireId,
+        op: GateOp,
+        b: WireId,
+        output: WireId,
+    ) -> Result<(), CircuitError> {
+        if self.drivers.contains_key(&output) || self.inputs.contains_key(&output) {
+            return Err(CircuitError::MultipleDrivers(self.name(output).to_string()));
+        }
+        self.drivers.insert(output, self.gates.len());
+        self.gates.push(Gate {
+            inputs: [a, b],
+            op,
+            output,
+        });
+        Ok(())
+    }
+
+    /** Swaps which gates drive `a` and `b` */
+    pub fn swap_outputs(&mut self, a: WireId, b: WireId) {
+        let gate_a = self.drivers.remove(&a);
+        let gate_b = self.drivers.remove(&b);
+        if let Some(idx) = gate_a {
+            self.gates[idx].output = b;
+            self.drivers.insert(b, idx);
+        }
+        if let Some(idx) = gate_b {
+            self.gates[idx].output = a;
+            self.drivers.insert(a, idx);
+        }
+    }
+
+    pub fn input(&self, wire: WireId) -> Option<bool> {
+        self.inputs.get(&wire).copied()
+    }
+
+    /** Gives an input wire a fixed value, failing if a gate drives it */
+    pub fn set_input(&mut self, wire: WireId, value: bool) -> Result<(), CircuitError> {
+        if self.drivers.contains_key(&wire) {
+            return Err(CircuitError::MultipleDrivers(self.name(wire).to_string()));
+        }
+        self.inputs.insert(wire, value);
+        Ok(())
+    }
+
+    /**
+     * The wires `prefix0`, `prefix1`, ... (zero padding allowed, so `z00`, `z01`, ...)
+     * in bit order, up to the first missing bit.
+     */
+    pub fn bus(&self, prefix: &str) -> Vec<WireId> {
+        let bits: HashMap<usize, WireId> = self
+            .names
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, name)| {
+                let suffix = name.strip_prefix(prefix)?;
+                if suffix.is_empty() || !suffix.bytes().all(|b| b.is_ascii_digit()) {
+                    return None;
+                }
+                Some((suffix.parse().ok()?, WireId(idx as u32)))
+            })
+            .collect();
+        (0..).map_while(|bit| bits.get(&bit).copied()).collect()
+    }
+
+    /** Sets the input wires of a bus to the bits of `value`, lowest bit first */
+    pub fn set_bus(&mut self, prefix: &str, value: u64) -> Result<(), CircuitError> {
+        for (bit, wire) in self.bus(prefix).into_iter().enumerate() {
+            self.set_input(wire, bit < 64 && (value >> bit) & 1 == 1)?;
+        }
+        Ok(())
+    }
+
+    /** Wires that are read by a gate but are neither an input nor a gate output */
+    pub fn undriven_wires(&self) -> Vec<WireId> {
+        let mut wires: Vec<WireId> = self
+            .gates
+            .iter()
+            .flat_map(|g| g.inputs)
+            .filter(|w| !self.drivers.contains_key(w) && !self.inputs.contains_key(w))
+            .collect();
+        wires.sort();
+        wires.dedup();
+        wires
+    }
+
+    /** Some cycle of gates, as the wires around it, if there is one */
+    pub fn find_loop(&self) -> Option<Vec<WireId>> {
+        // 0 = unvisited, 1 = on the current path, 2 = done
+        let mut state = vec![0u8; self.n_wires()];
+        let mut path = Vec::new();
+        for start in 0..self.n_wires() {
+            if state[start] == 0 {
+                if let Some(cycle) = self.loop_from(WireId(start as u32), &mut state, &mut path) {
+                    return Some(cycle);
+                }
+            }
+        }
+        None
+    }
+
+    /** Depth-first search backwards through the drivers of `wire` */
+    fn loop_from(
+        &self,
+        wire: WireId,
+        state: &mut [u8],
+        path: &mut Vec<WireId>,
+    ) -> Option<Vec<WireId>> {
+        match state[wire.index()] {
+            1 => {
+                let start = path
+                    .iter()
+                    .position(|w| *w == wire)
+                    .expect("Wire is on path");
+                let mut cycle = path[start..].to_vec();
+                cycle.reverse();
+                return Some(cycle);
+            }
+            2 => return None,
+            _ => (),
+        }
+        state[wire.index()] = 1;
+        path.push(wire);
+        if let Some(gate) = self.driver(wire) {
+            for input in gate.inputs {
+                if let Some(cycle) = self.loop_from(input, state, path) {
+                    return Some(cycle);
+                }
+            }
+        }
+        path.pop();
+        state[wire.index()] = 2;
+        None
+    }
+
+    fn names_of(&self, wires: &[WireId]) -> Vec<String> {
+        wires.iter().map(|w| self.name(*w).to_string()).collect()
+    }
+
+    /** Evaluates every gate, in topological order */
+    pub fn evaluate(&self) -> Result<Values, CircuitError> {
+        let undriven = self.undriven_wires();
+        if !undriven.is_empty() {
+            return Err(CircuitError::Undriven(self.names_of(&undriven)));
+        }
+
+        let mut values = vec![None; self.n_wires()];
+        for (wire, value) in self.inputs.iter() {
+            values[wire.index()] = Some(*value);
+        }
+        let mut readers: HashMap<WireId, Vec<usize>> = HashMap::new();
+        let mut missing_inputs = vec![0; self.gates.len()];
+        for (idx, gate) in self.gates.iter().enumerate() {
+            for input in gate.inputs {
+                readers.entry(input).or_default().push(idx);
+                if values[input.index()].is_none() {
+                    missing_inputs[idx] += 1;
+                }
+            }
+        }
+
+        let mut queue: VecDeque<usize> = (0..self.gates.len())
+            .filter(|idx| missing_inputs[*idx] == 0)
+            .collect();
+        let mut evaluated = 0;
+        while let Some(idx) = queue.pop_front() {
+            let gate = &self.gates[idx];
+            let [a, b] = gate
+                .inputs
+                .map(|w| values[w.index()].expect("Input is evaluated"));
+            values[gate.output.index()] = Some(gate.op.apply(a, b));
+            evaluated += 1;
+            for reader in readers.get(&gate.output).into_iter().flatten() {
+                missing_inputs[*reader] -= 1;
+                if missing_inputs[*reader] == 0 {
+                    queue.push_back(*reader);
+                }
+            }
+        }
+
+        if evaluated < self.gates.len() {
+            let cycle = self.find_loop().expect("Unevaluated gates lie on a loop");
+            return Err(CircuitError::Loop(self.names_of(&cycle)));
+        }
+        Ok(Values(values))
+    }
+
+    /** Evaluates the circuit and reads a bus as an integer, lowest bit first */
+    pub fn evaluate_bus(&self, prefix: &str) -> Result<u64, CircuitError> {
+        let bus = self.bus(prefix);
+        if bus.len() > 64 {
+            return Err(CircuitError::BusTooWide {
+                prefix: prefix.to_string(),
+                width: bus.len(),
+            });
+        }
+        let values = self.evaluate()?;
+        Ok(values.bus_value(&bus).expect("Every wire is evaluated"))
+    }
+}
+
+/** The value of every wire after `Netlist::evaluate` */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Values(Vec<Option<bool>>);
+
+impl Values {
+    /** None for wires that are not connected to anything */
+    pub fn get(&self, wire: WireId) -> Option<bool> {
+        self.0[wire.index()]
+    }
+
+    /** The wires as an integer, lowest bit first; None if one has no value */
+    pub fn bus_value(&self, bus: &[WireId]) -> Option<u64> {
+        bus.iter()
+            .rev()
+            .try_fold(0, |acc, w| Some((acc << 1) | self.get(*w)? as u64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::circuit::*;
+
+    const EXAMPLE: &str = "x00: 1
+x01: 1
+x02: 1
+y00: 0
+y01: 1
+y02: 0
+
+x00 AND y00 -> z00
+x01 XOR y01 -> z01
+x02 OR y02 -> z02
+";
+
+    #[test]
+    fn test_evaluate() {
+        let netlist = Netlist::parse(EXAMPLE).unwrap();
+        assert_eq!(netlist.bus("x").len(), 3);
+        assert_eq!(netlist.evaluate_bus("z"), Ok(4));
+        let z01 = netlist.id("z01").unwrap();
+        let gate = netlist.driver(z01).unwrap();
+        assert_eq!(netlist.name(gate.inputs[0]), "x01");
+        assert_eq!(
+            netlist.find_gate(netlist.id("y02").unwrap(), GateOp::Or, gate.inputs[0]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_set_bus_and_swap() {
+        let mut netlist = Netlist::parse(EXAMPLE).unwrap();
+        netlist.set_bus("x", 0b011).unwrap();
+        netlist.set_bus("y", 0b001).unwrap();
+        assert_eq!(netlist.evaluate_bus("z"), Ok(0b011));
+        let (z00, z02) = (netlist.id("z00").unwrap(), netlist.id("z02").unwrap());
+        netlist.swap_outputs(z00, z02);
+        assert_eq!(netlist.evaluate_bus("z"), Ok(0b110));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            Netlist::parse("a NAND b -> c").unwrap_err(),
+            CircuitError::UnknownOperator {
+                line: 1,
+                op: "NAND".to_string()
+            }
+        );
+        assert!(matches!(
+            Netlist::parse("a AND b"),
+            Err(CircuitError::Parse { line: 1, .. })
+        ));
+        assert_eq!(
+            Netlist::parse("a: 1\na AND b -> c\na OR b -> c").unwrap_err(),
+            CircuitError::MultipleDrivers("c".to_string())
+        );
+
+        let undriven = Netlist::parse("a: 1\na AND b -> z00").unwrap();
+        assert_eq!(
+            undriven.evaluate(),
+            Err(CircuitError::Undriven(vec!["b".to_string()]))
+        );
+
+        let looping = Netlist::parse("a: 1\na AND c -> b\nb OR a -> c\nc XOR a -> z00").unwrap();
+        let Err(CircuitError::Loop(cycle)) = looping.evaluate() else {
+            panic!("Expected a loop");
+        };
+        assert_eq!(cycle.len(), 2);
+        assert!(cycle.contains(&"b".to_string()) && cycle.contains(&"c".to_string()));
+    }
+}
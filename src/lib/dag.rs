@@ -0,0 +1,134 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/**
+* Paths found from a single source of an implicit DAG
+*/
+#[derive(Clone, Debug)]
+pub struct PathCount<N> {
+    pub source: N,
+    /** number of distinct paths from the source to any sink */
+    pub paths: u128,
+    /** every sink reachable from the source */
+    pub sinks: HashSet<N>,
+}
+
+enum Visit<N> {
+    Enter(N),
+    Exit(N),
+}
+
+/**
+* Counts the paths from each source to the sinks of the DAG described by `successors`.
+* Sinks are terminal: their successors are never explored.
+*
+* Results are memoised across all sources, so shared sub-paths are only explored once.
+* Panics if the graph reachable from a source contains a cycle, or if a count overflows u128.
+*/
+pub fn count_paths<N, S, F, I, P>(
+    sources: S,
+    mut successors: F,
+    mut is_sink: P,
+) -> Vec<PathCount<N>>
+where
+    N: Clone + Eq + Hash,
+    S: IntoIterator<Item = N>,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+    P: FnMut(&N) -> bool,
+{
+    let mut memo: HashMap<N, (u128, HashSet<N>)> = HashMap::new();
+    let mut children: HashMap<N, Vec<N>> = HashMap::new();
+    let mut in_progress: HashSet<N> = HashSet::new();
+
+    let mut results = Vec::new();
+    for source in sources {
+        let mut stack = vec![Visit::Enter(source.clone())];
+        while let Some(visit) = stack.pop() {
+            match visit {
+                Visit::Enter(node) => {
+                    if memo.contains_key(&node) {
+                        continue;
+                    }
+                    // a node is only re-entered while in progress through one of its descendants
+                    assert!(
+                        !in_progress.contains(&node),
+                        "Successor graph contains a cycle"
+                    );
+                    in_progress.insert(node.clone());
+                    let next: Vec<N> = if is_sink(&node) {
+                        Vec::new()
+                    } else {
+                        successors(&node).into_iter().collect()
+                    };
+                    stack.push(Visit::Exit(node.clone()));
+                    stack.extend(next.iter().cloned().map(Visit::Enter));
+                    children.insert(node, next);
+                }
+                Visit::Exit(node) => {
+                    let next = children.remove(&node).unwrap_or_default();
+                    let entry = if is_sink(&node) {
+                        (1, HashSet::from([node.clone()]))
+                    } else {
+                        next.iter()
+                            .fold((0u128, HashSet::new()), |(paths, mut sinks), child| {
+                                let (child_paths, child_sinks) =
+                                    memo.get(child).expect("Child should be memoised");
+                                sinks.extend(child_sinks.iter().cloned());
+                                let paths = paths
+                                    .checked_add(*child_paths)
+                                    .expect("Path count overflowed u128");
+                                (paths, sinks)
+                            })
+                    };
+                    in_progress.remove(&node);
+                    memo.insert(node, entry);
+                }
+            }
+        }
+
+        let (paths, sinks) = memo
+            .get(&source)
+            .expect("Source should be memoised")
+            .clone();
+        results.push(PathCount {
+            source,
+            paths,
+            sinks,
+        });
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diamond() {
+        // 0 -> 1, 2 -> 3
+        let succ = |n: &u32| match n {
+            0 => vec![1, 2],
+            1 | 2 => vec![3],
+            _ => vec![],
+        };
+        let result = count_paths([0, 1], succ, |n| *n == 3);
+        assert_eq!(result[0].paths, 2);
+        assert_eq!(result[0].sinks, HashSet::from([3]));
+        assert_eq!(result[1].paths, 1);
+    }
+
+    #[test]
+    fn test_exponential_paths() {
+        // steps of 1 or 2 from 0 to 100: fib(101) paths, which does not fit in a u64
+        let succ = |n: &u32| (n + 1..=n + 2).filter(|x| *x <= 100);
+        let result = count_paths([0], succ, |n| *n == 100);
+        assert_eq!(result[0].paths, 573_147_844_013_817_084_101);
+    }
+
+    #[test]
+    #[should_panic(expected = "cycle")]
+    fn test_cycle() {
+        count_paths([0u32], |n| vec![(n + 1) % 3], |_| false);
+    }
+}
@@ -11,6 +11,37 @@ pub enum Dir {
 }
 
 impl Dir {
+    /** All eight directions, clockwise from north */
+    pub const ALL: [Dir; 8] = [
+        Dir::N,
+        Dir::NE,
+        Dir::E,
+        Dir::SE,
+        Dir::S,
+        Dir::SW,
+        Dir::W,
+        Dir::NW,
+    ];
+
+    /** The four cardinal directions, clockwise from north */
+    pub const CARDINAL: [Dir; 4] = [Dir::N, Dir::E, Dir::S, Dir::W];
+
+    /** The four diagonal directions, clockwise from north-east */
+    pub const DIAGONAL: [Dir; 4] = [Dir::NE, Dir::SE, Dir::SW, Dir::NW];
+
+    pub fn is_cardinal(&self) -> bool {
+        matches!(self, Dir::N | Dir::E | Dir::S | Dir::W)
+    }
+
+    pub fn is_diagonal(&self) -> bool {
+        !self.is_cardinal()
+    }
+
+    /** The arrow character for a cardinal direction, e.g. `'^'` for north */
+    pub fn to_arrow(&self) -> Option<char> {
+        Dir4::try_from(*self).ok().map(|d| d.to_arrow())
+    }
+
     pub fn delta(&self) -> (i32, i32) {
         use Dir::*;
         match self {
@@ -66,4 +97,259 @@ impl Dir {
             NW => SE,
         }
     }
+
+    /** Rotates by `steps` eighths of a turn (45° each), clockwise if positive */
+    pub fn rotate_by(&self, steps: i32) -> Self {
+        let degrees = self.to_degrees() as i32 + steps.rem_euclid(8) * 45;
+        Dir::from_degrees(degrees).expect("45° steps always land on a direction")
+    }
+
+    /**
+     * The shortest rotation from `self` to `other` in 45° steps, clockwise if positive.
+     * Opposite directions give 4.
+     */
+    pub fn steps_to(&self, other: Dir) -> i32 {
+        let diff = other.to_degrees() as i32 - self.to_degrees() as i32;
+        (diff / 45 + 3).rem_euclid(8) - 3
+    }
+
+    /**
+     * Number of quarter turns needed to get from heading `a` to heading `b`, e.g. 2 for
+     * opposite directions, or None if the headings are not a multiple of 90° apart.
+     * Use `steps_to` to count 45° steps between any two headings.
+     */
+    pub fn turns_between(a: Dir, b: Dir) -> Option<u32> {
+        let steps = a.steps_to(b).unsigned_abs();
+        steps.is_multiple_of(2).then_some(steps / 2)
+    }
+
+    pub fn apply(&self, turn: Turn) -> Self {
+        match turn {
+            Turn::Straight => *self,
+            Turn::Right => self.rotate_clockwise(),
+            Turn::Back => self.opposite(),
+            Turn::Left => self.rotate_counterclockwise(),
+        }
+    }
+
+    /** Compass bearing in degrees, clockwise from north */
+    pub fn to_degrees(&self) -> u32 {
+        use Dir::*;
+        match self {
+            N => 0,
+            NE => 45,
+            E => 90,
+            SE => 135,
+            S => 180,
+            SW => 225,
+            W => 270,
+            NW => 315,
+        }
+    }
+
+    /** The direction with the given compass bearing, if it is a multiple of 45° */
+    pub fn from_degrees(degrees: i32) -> Option<Self> {
+        let degrees = degrees.rem_euclid(360) as u32;
+        Dir::ALL.into_iter().find(|dir| dir.to_degrees() == degrees)
+    }
+}
+
+/** A turn relative to the current heading */
+#[derive(Copy, Clone, PartialEq, Hash, Eq, Debug)]
+pub enum Turn {
+    Straight,
+    Right,
+    Back,
+    Left,
+}
+
+impl Turn {
+    /**
+     * The turn that takes heading `from` to heading `to`, if they are a multiple of 90°
+     * apart. Opposite headings give `Back`.
+     */
+    pub fn between(from: Dir, to: Dir) -> Option<Turn> {
+        match from.steps_to(to) {
+            0 => Some(Turn::Straight),
+            2 => Some(Turn::Right),
+            4 => Some(Turn::Back),
+            -2 => Some(Turn::Left),
+            _ => None,
+        }
+    }
+}
+
+/** Returned when a character does not name a direction */
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ParseDirError(pub char);
+
+/** Parses arrows (`^ > v <`) and compass letters (`N E S W`) */
+impl TryFrom<char> for Dir {
+    type Error = ParseDirError;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        Dir4::try_from(value).map(Dir::from)
+    }
+}
+
+/**
+* One of the four cardinal directions, for code that must never move diagonally
+*/
+#[derive(Copy, Clone, PartialEq, Hash, Eq, Debug)]
+pub enum Dir4 {
+    N,
+    E,
+    S,
+    W,
+}
+
+impl Dir4 {
+    /** All four directions, clockwise from north */
+    pub const ALL: [Dir4; 4] = [Dir4::N, Dir4::E, Dir4::S, Dir4::W];
+
+    pub fn delta(&self) -> (i32, i32) {
+        Dir::from(*self).delta()
+    }
+
+    pub fn rotate_clockwise(&self) -> Self {
+        use Dir4::*;
+        match self {
+            N => E,
+            E => S,
+            S => W,
+            W => N,
+        }
+    }
+
+    pub fn rotate_counterclockwise(&self) -> Self {
+        use Dir4::*;
+        match self {
+            N => W,
+            E => N,
+            S => E,
+            W => S,
+        }
+    }
+
+    pub fn opposite(&self) -> Self {
+        use Dir4::*;
+        match self {
+            N => S,
+            E => W,
+            S => N,
+            W => E,
+        }
+    }
+
+    /** The arrow character, e.g. `'^'` for north */
+    pub fn to_arrow(&self) -> char {
+        use Dir4::*;
+        match self {
+            N => '^',
+            E => '>',
+            S => 'v',
+            W => '<',
+        }
+    }
+}
+
+impl From<Dir4> for Dir {
+    fn from(value: Dir4) -> Self {
+        match value {
+            Dir4::N => Dir::N,
+            Dir4::E => Dir::E,
+            Dir4::S => Dir::S,
+            Dir4::W => Dir::W,
+        }
+    }
+}
+
+/** Fails for diagonal directions */
+impl TryFrom<Dir> for Dir4 {
+    type Error = Dir;
+
+    fn try_from(value: Dir) -> Result<Self, Self::Error> {
+        match value {
+            Dir::N => Ok(Dir4::N),
+            Dir::E => Ok(Dir4::E),
+            Dir::S => Ok(Dir4::S),
+            Dir::W => Ok(Dir4::W),
+            diagonal => Err(diagonal),
+        }
+    }
+}
+
+/** Parses arrows (`^ > v <`) and compass letters (`N E S W`) */
+impl TryFrom<char> for Dir4 {
+    type Error = ParseDirError;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            '^' | 'N' => Ok(Dir4::N),
+            '>' | 'E' => Ok(Dir4::E),
+            'v' | 'S' => Ok(Dir4::S),
+            '<' | 'W' => Ok(Dir4::W),
+            _ => Err(ParseDirError(value)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_direction_sets() {
+        assert!(Dir::CARDINAL.iter().all(Dir::is_cardinal));
+        assert!(Dir::DIAGONAL.iter().all(Dir::is_diagonal));
+        assert_eq!(Dir::ALL.len(), 8);
+        assert_eq!(Dir4::ALL.map(Dir::from).to_vec(), Dir::CARDINAL.to_vec());
+        assert_eq!(Dir4::try_from(Dir::NE), Err(Dir::NE));
+    }
+
+    #[test]
+    fn test_angles() {
+        assert_eq!(Dir::N.rotate_by(1), Dir::NE);
+        assert_eq!(Dir::N.rotate_by(-3), Dir::SW);
+        assert_eq!(Dir::W.rotate_by(10), Dir::N);
+        assert_eq!(Dir::E.steps_to(Dir::N), -2);
+        assert_eq!(Dir::NE.steps_to(Dir::SW), 4);
+        assert_eq!(Dir::turns_between(Dir::E, Dir::W), Some(2));
+        assert_eq!(Dir::turns_between(Dir::NW, Dir::NE), Some(1));
+        assert_eq!(Dir::turns_between(Dir::S, Dir::S), Some(0));
+        assert_eq!(Dir::turns_between(Dir::N, Dir::NE), None);
+        assert_eq!(Dir::turns_between(Dir::SE, Dir::N), None);
+        assert_eq!(Dir::SW.to_degrees(), 225);
+        assert_eq!(Dir::from_degrees(-90), Some(Dir::W));
+        assert_eq!(Dir::from_degrees(30), None);
+        for (i, dir) in Dir::ALL.into_iter().enumerate() {
+            assert_eq!(dir.to_degrees(), 45 * i as u32);
+            assert_eq!(dir.rotate_by(2), dir.rotate_clockwise());
+            assert_eq!(dir.rotate_by(-2), dir.rotate_counterclockwise());
+            assert_eq!(dir.rotate_by(4), dir.opposite());
+        }
+    }
+
+    #[test]
+    fn test_turns() {
+        for dir in Dir::ALL {
+            for turn in [Turn::Straight, Turn::Right, Turn::Back, Turn::Left] {
+                assert_eq!(Turn::between(dir, dir.apply(turn)), Some(turn));
+            }
+        }
+        assert_eq!(Dir::NE.apply(Turn::Right), Dir::SE);
+        assert_eq!(Turn::between(Dir::N, Dir::NE), None);
+        assert_eq!(Turn::between(Dir::SE, Dir::NW), Some(Turn::Back));
+    }
+
+    #[test]
+    fn test_char_parsing() {
+        assert_eq!(Dir::try_from('^'), Ok(Dir::N));
+        assert_eq!(Dir::try_from('W'), Ok(Dir::W));
+        assert_eq!(Dir::try_from('x'), Err(ParseDirError('x')));
+        for dir in Dir4::ALL {
+            assert_eq!(Dir4::try_from(dir.to_arrow()), Ok(dir));
+        }
+        assert_eq!(Dir::SE.to_arrow(), None);
+    }
 }
@@ -0,0 +1,253 @@
+use std::cmp::Ordering;
+use std::fmt::{self, Display};
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Sub, SubAssign};
+
+use crate::bitset::{cmp_words, fmt_values, trim_words, BitSet, BitSetIter, BitSetOps};
+
+/**
+* A set of u64 values below `64 * WORDS`, stored inline so that it is `Copy`
+* and never allocates
+*/
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FixedBitSet<const WORDS: usize> {
+    words: [u64; WORDS],
+}
+
+/** Returned when a value does not fit into a `FixedBitSet` */
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CapacityExceeded(pub u64);
+
+impl<const WORDS: usize> FixedBitSet<WORDS> {
+    pub const CAPACITY: usize = 64 * WORDS;
+
+    pub fn new() -> Self {
+        Self { words: [0; WORDS] }
+    }
+
+    /** Inserts a value, failing if it is beyond the capacity of the set */
+    pub fn try_insert(&mut self, value: u64) -> Result<(), CapacityExceeded> {
+        let (word, idx) = (value / 64, value % 64);
+        let target = self
+            .words
+            .get_mut(word as usize)
+            .ok_or(CapacityExceeded(value))?;
+        *target |= 1 << idx;
+        Ok(())
+    }
+
+    pub fn capacity(&self) -> usize {
+        Self::CAPACITY
+    }
+}
+
+impl<const WORDS: usize> BitSetOps for FixedBitSet<WORDS> {
+    type Iter<'a> = BitSetIter<'a>;
+
+    /** Panics if the value is beyond the capacity of the set */
+    fn insert(&mut self, value: u64) {
+        self.try_insert(value)
+            .unwrap_or_else(|_| panic!("{} does not fit in a FixedBitSet<{}>", value, WORDS));
+    }
+
+    fn remove(&mut self, value: u64) {
+        let (word, idx) = (value / 64, value % 64);
+        if let Some(target) = self.words.get_mut(word as usize) {
+            *target &= !(1 << idx);
+        }
+    }
+
+    fn contains(&self, value: u64) -> bool {
+        let (word, idx) = (value / 64, value % 64);
+        self.words
+            .get(word as usize)
+            .is_some_and(|v| (*v & (1 << idx)) > 0)
+    }
+
+    fn len(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    fn iter(&self) -> BitSetIter<'_> {
+        BitSetIter::new(&self.words)
+    }
+
+    fn union_inplace(&mut self, other: &Self) {
+        for (target, val) in self.words.iter_mut().zip(other.words.iter()) {
+            *target |= val;
+        }
+    }
+
+    fn intersection_inplace(&mut self, other: &Self) {
+        for (target, val) in self.words.iter_mut().zip(other.words.iter()) {
+            *target &= val;
+        }
+    }
+
+    fn difference_inplace(&mut self, other: &Self) {
+        for (target, val) in self.words.iter_mut().zip(other.words.iter()) {
+            *target &= !val;
+        }
+    }
+
+    fn symmetric_difference_inplace(&mut self, other: &Self) {
+        for (target, val) in self.words.iter_mut().zip(other.words.iter()) {
+            *target ^= val;
+        }
+    }
+
+    fn intersection_len(&self, other: &Self) -> usize {
+        self.words
+            .iter()
+            .zip(other.words.iter())
+            .map(|(a, b)| (a & b).count_ones() as usize)
+            .sum()
+    }
+
+    fn is_disjoint(&self, other: &Self) -> bool {
+        self.words
+            .iter()
+            .zip(other.words.iter())
+            .all(|(a, b)| a & b == 0)
+    }
+}
+
+/** Writes values as `{1, 4..=9}`, like `BitSet` */
+impl<const WORDS: usize> Display for FixedBitSet<WORDS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_values(self.iter(), f)
+    }
+}
+
+impl<const WORDS: usize> Default for FixedBitSet<WORDS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/**
+* Orders sets by their values in increasing order, the same way as `BitSet`
+*/
+impl<const WORDS: usize> Ord for FixedBitSet<WORDS> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        cmp_words(&self.words, &other.words)
+    }
+}
+
+impl<const WORDS: usize> PartialOrd for FixedBitSet<WORDS> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+macro_rules! impl_set_op {
+    ($op:ident, $method:ident, $assign_op:ident, $assign_method:ident, $inplace:ident) => {
+        impl<const WORDS: usize> $assign_op for FixedBitSet<WORDS> {
+            fn $assign_method(&mut self, rhs: Self) {
+                self.$inplace(&rhs);
+            }
+        }
+
+        impl<const WORDS: usize> $op for FixedBitSet<WORDS> {
+            type Output = Self;
+
+            fn $method(mut self, rhs: Self) -> Self {
+                self.$inplace(&rhs);
+                self
+            }
+        }
+    };
+}
+
+impl_set_op!(
+    BitAnd,
+    bitand,
+    BitAndAssign,
+    bitand_assign,
+    intersection_inplace
+);
+impl_set_op!(BitOr, bitor, BitOrAssign, bitor_assign, union_inplace);
+impl_set_op!(
+    BitXor,
+    bitxor,
+    BitXorAssign,
+    bitxor_assign,
+    symmetric_difference_inplace
+);
+impl_set_op!(Sub, sub, SubAssign, sub_assign, difference_inplace);
+
+impl<const WORDS: usize> From<FixedBitSet<WORDS>> for BitSet {
+    fn from(value: FixedBitSet<WORDS>) -> Self {
+        BitSet::from_words(value.words.to_vec())
+    }
+}
+
+impl<const WORDS: usize> TryFrom<&BitSet> for FixedBitSet<WORDS> {
+    type Error = CapacityExceeded;
+
+    fn try_from(value: &BitSet) -> Result<Self, Self::Error> {
+        let src = trim_words(value.words());
+        if src.len() > WORDS {
+            return Err(CapacityExceeded(value.last().expect("Set is not empty")));
+        }
+        let mut words = [0; WORDS];
+        words[..src.len()].copy_from_slice(src);
+        Ok(Self { words })
+    }
+}
+
+impl<const WORDS: usize> FromIterator<u64> for FixedBitSet<WORDS> {
+    /** Panics if any value is beyond the capacity of the set */
+    fn from_iter<I: IntoIterator<Item = u64>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for value in iter {
+            set.insert(value);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bit_set;
+    use crate::bitset::{BitSet, BitSetOps};
+    use crate::fixed_bitset::*;
+
+    #[test]
+    fn test_insert_remove() {
+        let mut v = FixedBitSet::<2>::new();
+        v.insert(1);
+        v.insert(127);
+        v.insert(1);
+        assert_eq!(v.len(), 2);
+        assert!(v.contains(127));
+        assert!(!v.contains(128));
+        v.remove(1);
+        assert_eq!(v.iter().collect::<Vec<_>>(), vec![127]);
+        assert_eq!(v.try_insert(128), Err(CapacityExceeded(128)));
+    }
+
+    #[test]
+    fn test_set_ops() {
+        let a: FixedBitSet<2> = [1, 4, 5, 100].into_iter().collect();
+        let b: FixedBitSet<2> = [4, 5, 6].into_iter().collect();
+        let copy = a;
+        assert_eq!((a & b).iter().collect::<Vec<_>>(), vec![4, 5]);
+        assert_eq!((a | b).len(), 5);
+        assert_eq!((a ^ b).iter().collect::<Vec<_>>(), vec![1, 6, 100]);
+        assert_eq!((a - b).iter().collect::<Vec<_>>(), vec![1, 100]);
+        assert!((a & b).is_subset(&a));
+        assert_eq!(a, copy);
+    }
+
+    #[test]
+    fn test_conversions() {
+        let set = bit_set!(1, 4, 5, 100);
+        let fixed = FixedBitSet::<2>::try_from(&set).unwrap();
+        assert_eq!(BitSet::from(fixed), set);
+        assert_eq!(FixedBitSet::<1>::try_from(&set), Err(CapacityExceeded(100)));
+
+        let mut padded = BitSet::with_capacity(1000);
+        padded.insert(3);
+        assert!(FixedBitSet::<1>::try_from(&padded).is_ok());
+    }
+}
@@ -1,5 +1,6 @@
 use crate::Dir;
 use crate::Loc;
+use crate::{GridIndex, Vec2};
 
 use std::ops::{Index, IndexMut};
 
@@ -13,26 +14,23 @@ impl<T> Grid<T> {
         self.0[0].len()
     }
 
-    pub fn in_bounds(&self, loc: &Loc) -> bool {
-        let (r, c) = loc;
-        (*r < self.n_rows()) && (*c < self.n_cols())
+    pub fn in_bounds<I: GridIndex>(&self, loc: &I) -> bool {
+        self.loc(loc).is_some()
     }
 
-    pub fn at(&self, loc: &Loc) -> Option<&T> {
-        if !self.in_bounds(loc) {
-            return None;
-        }
-        let (r, c) = *loc;
+    /** The grid index of `loc`, if it lies inside the grid */
+    pub fn loc<I: GridIndex>(&self, loc: &I) -> Option<Loc> {
+        loc.to_point()?.to_loc(self.n_rows(), self.n_cols())
+    }
+
+    pub fn at<I: GridIndex>(&self, loc: &I) -> Option<&T> {
+        let Loc(r, c) = self.loc(loc)?;
         Some(&self.0[r][c])
     }
 
-    pub fn at_mut(&mut self, loc: &Loc) -> Option<&mut T> {
-        if !self.in_bounds(loc) {
-            None
-        } else {
-            let (r, c) = *loc;
-            Some(&mut self.0[r][c])
-        }
+    pub fn at_mut<I: GridIndex>(&mut self, loc: &I) -> Option<&mut T> {
+        let Loc(r, c) = self.loc(loc)?;
+        Some(&mut self.0[r][c])
     }
 
     pub fn iter_rows(&self) -> std::slice::Iter<'_, Vec<T>> {
@@ -44,46 +42,23 @@ impl<T> Grid<T> {
     }
 
     // attempt to step one step from the current position
-    pub fn step(&self, loc: &Loc, direction: Dir) -> Option<Loc> {
-        let (r, c) = *loc;
-        let (dr, dc) = direction.delta();
-
-        // out of bounds negative
-        if (r == 0 && dr < 0) || (c == 0 && dc < 0) {
-            return None;
-        }
+    pub fn step<I: GridIndex, D: Into<Dir>>(&self, loc: &I, direction: D) -> Option<Loc> {
+        self.step_n(loc, direction, 1usize)
+    }
 
-        let nr = ((r as i32) + dr) as usize;
-        let nc = ((c as i32) + dc) as usize;
-        let new_pos = (nr, nc);
-        if !self.in_bounds(&new_pos) {
-            return None;
-        }
-        Some(new_pos)
-    }
-
-    pub fn step_n<N: Into<usize>>(&self, loc: &Loc, direction: Dir, step_count: N) -> Option<Loc> {
-        let (r, c) = *loc;
-        let r = r as isize;
-        let c = c as isize;
-        let (dr, dc) = direction.delta();
-        let dr = dr as isize;
-        let dc = dc as isize;
-
-        let step_count: isize = step_count.into().try_into().ok()?;
-        let nr: isize = r + step_count * dr;
-        let nc: isize = c + step_count * dc;
-        if nr < 0
-            || nr >= self.n_rows().try_into().unwrap()
-            || nc < 0
-            || nc >= self.n_cols().try_into().unwrap()
-        {
-            return None;
-        }
-        Some((nr as usize, nc as usize))
+    pub fn step_n<I: GridIndex, D: Into<Dir>, N: Into<usize>>(
+        &self,
+        loc: &I,
+        direction: D,
+        step_count: N,
+    ) -> Option<Loc> {
+        let (dr, dc) = direction.into().delta();
+        let step_count = i64::try_from(step_count.into()).ok()?;
+        let offset = Vec2::new(dc as i64, dr as i64) * step_count;
+        self.loc(&loc.to_point()?.checked_add(offset)?)
     }
 
-    pub fn step_get(&self, loc: &Loc, direction: Dir) -> Option<&T> {
+    pub fn step_get<I: GridIndex, D: Into<Dir>>(&self, loc: &I, direction: D) -> Option<&T> {
         self.step(loc, direction).map(|l| &self[&l])
     }
 
@@ -122,7 +97,7 @@ impl<'a, T> Iterator for GridIterWithLoc<'a, T> {
         if self.r == self.grid.n_rows() {
             return None;
         }
-        let idx = (self.r, self.c);
+        let idx = Loc(self.r, self.c);
         let item = &self.grid[&idx];
         self.c += 1;
         if self.c == self.grid.n_cols() {
@@ -133,18 +108,31 @@ impl<'a, T> Iterator for GridIterWithLoc<'a, T> {
     }
 }
 
-impl<T> Index<&Loc> for Grid<T> {
+impl<T> Grid<T> {
+    fn index_loc<I: GridIndex>(&self, index: I) -> Loc {
+        self.loc(&index).unwrap_or_else(|| {
+            panic!(
+                "{:?} is outside of a {}x{} Grid",
+                index,
+                self.n_rows(),
+                self.n_cols()
+            )
+        })
+    }
+}
+
+impl<T, I: GridIndex> Index<I> for Grid<T> {
     type Output = T;
 
-    fn index(&self, index: &Loc) -> &Self::Output {
-        let (r, c) = *index;
+    fn index(&self, index: I) -> &Self::Output {
+        let Loc(r, c) = self.index_loc(index);
         &self.0[r][c]
     }
 }
 
-impl<T> IndexMut<&Loc> for Grid<T> {
-    fn index_mut(&mut self, index: &Loc) -> &mut Self::Output {
-        let (r, c) = *index;
+impl<T, I: GridIndex> IndexMut<I> for Grid<T> {
+    fn index_mut(&mut self, index: I) -> &mut Self::Output {
+        let Loc(r, c) = self.index_loc(index);
         &mut self.0[r][c]
     }
 }
@@ -0,0 +1,124 @@
+use std::ops::{Index, IndexMut};
+
+use crate::Vec3;
+
+/**
+* Index into a `Grid3`, as (layer, row, col).
+* A distinct type rather than a tuple, like `Loc`.
+*/
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Loc3(pub usize, pub usize, pub usize);
+
+/**
+* A dense 3D grid stored as layers of rows, the same way `Grid` stores rows.
+* Signed `Vec3` positions map onto it with x the column, y the row and z the layer.
+*/
+pub struct Grid3<T>(pub Vec<Vec<Vec<T>>>);
+
+impl<T> Grid3<T> {
+    pub fn n_layers(&self) -> usize {
+        self.0.len()
+    }
+    pub fn n_rows(&self) -> usize {
+        self.0[0].len()
+    }
+    pub fn n_cols(&self) -> usize {
+        self.0[0][0].len()
+    }
+
+    pub fn in_bounds(&self, loc: &Loc3) -> bool {
+        let Loc3(l, r, c) = *loc;
+        l < self.n_layers() && r < self.n_rows() && c < self.n_cols()
+    }
+
+    /** The index of a signed position, if it lies inside the grid */
+    pub fn loc(&self, pos: Vec3<i64>) -> Option<Loc3> {
+        let loc = Loc3(
+            usize::try_from(pos.z).ok()?,
+            usize::try_from(pos.y).ok()?,
+            usize::try_from(pos.x).ok()?,
+        );
+        self.in_bounds(&loc).then_some(loc)
+    }
+
+    pub fn at(&self, loc: &Loc3) -> Option<&T> {
+        let Loc3(l, r, c) = *loc;
+        self.0.get(l)?.get(r)?.get(c)
+    }
+
+    pub fn at_mut(&mut self, loc: &Loc3) -> Option<&mut T> {
+        let Loc3(l, r, c) = *loc;
+        self.0.get_mut(l)?.get_mut(r)?.get_mut(c)
+    }
+
+    /** The in-bounds locations sharing a face with `loc` */
+    pub fn neighbours6(&self, loc: &Loc3) -> impl Iterator<Item = Loc3> + '_ {
+        let Loc3(l, r, c) = *loc;
+        Vec3::new(c as i64, r as i64, l as i64)
+            .neighbours6()
+            .filter_map(|pos| self.loc(pos))
+    }
+
+    /** The in-bounds locations sharing a face, edge or corner with `loc` */
+    pub fn neighbours26(&self, loc: &Loc3) -> impl Iterator<Item = Loc3> + '_ {
+        let Loc3(l, r, c) = *loc;
+        Vec3::new(c as i64, r as i64, l as i64)
+            .neighbours26()
+            .filter_map(|pos| self.loc(pos))
+    }
+
+    /** Iterates over every cell with its location, layer by layer in row-major order */
+    pub fn iter_with_loc(&self) -> impl Iterator<Item = (Loc3, &T)> {
+        self.0.iter().enumerate().flat_map(|(l, layer)| {
+            layer.iter().enumerate().flat_map(move |(r, row)| {
+                row.iter()
+                    .enumerate()
+                    .map(move |(c, value)| (Loc3(l, r, c), value))
+            })
+        })
+    }
+}
+
+impl<T> Grid3<T>
+where
+    T: Copy,
+{
+    pub fn new(n_layers: usize, n_rows: usize, n_cols: usize, value: T) -> Self {
+        Grid3(vec![vec![vec![value; n_cols]; n_rows]; n_layers])
+    }
+}
+
+impl<T> Index<&Loc3> for Grid3<T> {
+    type Output = T;
+
+    fn index(&self, index: &Loc3) -> &Self::Output {
+        let Loc3(l, r, c) = *index;
+        &self.0[l][r][c]
+    }
+}
+
+impl<T> IndexMut<&Loc3> for Grid3<T> {
+    fn index_mut(&mut self, index: &Loc3) -> &mut Self::Output {
+        let Loc3(l, r, c) = *index;
+        &mut self.0[l][r][c]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grid3() {
+        let mut g = Grid3::new(2, 3, 4, 0);
+        g[&Loc3(1, 2, 3)] = 7;
+        assert_eq!(g.at(&Loc3(1, 2, 3)), Some(&7));
+        assert_eq!(g.at(&Loc3(2, 0, 0)), None);
+        assert_eq!(g.loc(Vec3::new(3, 2, 1)), Some(Loc3(1, 2, 3)));
+        assert_eq!(g.loc(Vec3::new(-1, 0, 0)), None);
+        assert_eq!(g.neighbours6(&Loc3(0, 0, 0)).count(), 3);
+        assert_eq!(g.neighbours26(&Loc3(0, 1, 1)).count(), 17);
+        assert_eq!(g.iter_with_loc().filter(|(_, v)| **v == 7).count(), 1);
+        assert_eq!(g.iter_with_loc().count(), 24);
+    }
+}
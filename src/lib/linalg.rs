@@ -0,0 +1,506 @@
+use std::cmp::Ordering;
+use std::fmt::{self, Display};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::math::{crt, ext_gcd, gcd, lcm};
+
+/**
+* An exact fraction, always stored in lowest terms with a positive denominator.
+* Backed by i128, which is ample for eliminating small systems of puzzle-sized numbers.
+* Arithmetic panics on overflow rather than wrapping, even in release builds.
+*/
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Rational {
+    num: i128,
+    den: i128,
+}
+
+impl Rational {
+    pub const ZERO: Rational = Rational { num: 0, den: 1 };
+    pub const ONE: Rational = Rational { num: 1, den: 1 };
+
+    /** Panics if `den` is zero */
+    pub fn new(num: i128, den: i128) -> Self {
+        assert!(den != 0, "Rational with a zero denominator");
+        let g = gcd(num.unsigned_abs(), den.unsigned_abs()) as i128;
+        let sign = den.signum();
+        Rational {
+            num: sign * num / g,
+            den: sign * den / g,
+        }
+    }
+
+    pub fn numer(&self) -> i128 {
+        self.num
+    }
+
+    pub fn denom(&self) -> i128 {
+        self.den
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.num == 0
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.den == 1
+    }
+
+    /** The value, if it is a whole number */
+    pub fn to_integer(&self) -> Option<i128> {
+        self.is_integer().then_some(self.num)
+    }
+
+    pub fn floor(&self) -> i128 {
+        self.num.div_euclid(self.den)
+    }
+
+    pub fn ceil(&self) -> i128 {
+        -(-self.num).div_euclid(self.den)
+    }
+
+    /** None if an intermediate product or sum overflows */
+    pub fn checked_add(self, rhs: Rational) -> Option<Rational> {
+        let num = self
+            .num
+            .checked_mul(rhs.den)?
+            .checked_add(rhs.num.checked_mul(self.den)?)?;
+        Some(Rational::new(num, self.den.checked_mul(rhs.den)?))
+    }
+
+    /** None if an intermediate product overflows */
+    pub fn checked_mul(self, rhs: Rational) -> Option<Rational> {
+        Some(Rational::new(
+            self.num.checked_mul(rhs.num)?,
+            self.den.checked_mul(rhs.den)?,
+        ))
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(value: i64) -> Self {
+        Rational::new(value as i128, 1)
+    }
+}
+
+impl From<i128> for Rational {
+    fn from(value: i128) -> Self {
+        Rational::new(value, 1)
+    }
+}
+
+impl Add for Rational {
+    type Output = Rational;
+
+    fn add(self, rhs: Rational) -> Self::Output {
+        self.checked_add(rhs).expect("Rational overflow")
+    }
+}
+
+impl Sub for Rational {
+    type Output = Rational;
+
+    fn sub(self, rhs: Rational) -> Self::Output {
+        self + -rhs
+    }
+}
+
+impl Mul for Rational {
+    type Output = Rational;
+
+    fn mul(self, rhs: Rational) -> Self::Output {
+        self.checked_mul(rhs).expect("Rational overflow")
+    }
+}
+
+impl Div for Rational {
+    type Output = Rational;
+
+    /** Panics when dividing by zero */
+    fn div(self, rhs: Rational) -> Self::Output {
+        assert!(!rhs.is_zero(), "Rational division by zero");
+        self.checked_mul(Rational::new(rhs.den, rhs.num))
+            .expect("Rational overflow")
+    }
+}
+
+impl Neg for Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Self::Output {
+        Rational {
+            num: self.num.checked_neg().expect("Rational overflow"),
+            den: self.den,
+        }
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let lhs = self.num.checked_mul(other.den).expect("Rational overflow");
+        lhs.cmp(&other.num.checked_mul(self.den).expect("Rational overflow"))
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+/** The solutions of a linear system `A x = b` */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Solution {
+    Unique(Vec<Rational>),
+    None,
+    /**
+     * Every `particular + t_1 * directions[0] + t_2 * directions[1] + ...` is a solution.
+     * There is one direction per free variable, and each direction has a 1 in the position
+     * of its free variable and 0 in the positions of the other free variables.
+     */
+    Infinite {
+        particular: Vec<Rational>,
+        directions: Vec<Vec<Rational>>,
+    },
+}
+
+/**
+* Solves `A x = b` exactly by Gauss-Jordan elimination over the rationals.
+* `a` is given as rows, and must have one row per entry of `b`.
+*/
+pub fn solve(a: &[Vec<i64>], b: &[i64]) -> Solution {
+    assert_eq!(a.len(), b.len(), "Linear system needs one target per row");
+    let n_vars = a.first().map_or(0, |row| row.len());
+    let mut rows: Vec<Vec<Rational>> = a
+        .iter()
+        .zip(b)
+        .map(|(row, target)| {
+            assert_eq!(row.len(), n_vars, "Linear system rows differ in length");
+            row.iter()
+                .chain(std::iter::once(target))
+                .map(|v| Rational::from(*v))
+                .collect()
+        })
+        .collect();
+
+    let mut pivots = Vec::new();
+    for col in 0..n_vars {
+        let row = pivots.len();
+        let Some(found) = (row..rows.len()).find(|r| !rows[*r][col].is_zero()) else {
+            continue;
+        };
+        rows.swap(row, found);
+        let scale = rows[row][col];
+        for v in rows[row].iter_mut() {
+            *v = *v / scale;
+        }
+        for other in 0..rows.len() {
+            let factor = rows[other][col];
+            if other == row || factor.is_zero() {
+                continue;
+            }
+            let pivot_row = rows[row].clone();
+            for (target, v) in rows[other].iter_mut().zip(pivot_row).skip(col) {
+                *target = *target - factor * v;
+            }
+        }
+        pivots.push(col);
+    }
+
+    // a leftover row reading 0 = c with c != 0
+    if rows[pivots.len()..]
+        .iter()
+        .any(|row| !row[n_vars].is_zero())
+    {
+        return Solution::None;
+    }
+
+    let mut particular = vec![Rational::ZERO; n_vars];
+    for (row, col) in pivots.iter().enumerate() {
+        particular[*col] = rows[row][n_vars];
+    }
+    if pivots.len() == n_vars {
+        return Solution::Unique(particular);
+    }
+
+    let directions = (0..n_vars)
+        .filter(|col| !pivots.contains(col))
+        .map(|free| {
+            let mut direction = vec![Rational::ZERO; n_vars];
+            direction[free] = Rational::ONE;
+            for (row, col) in pivots.iter().enumerate() {
+                direction[*col] = -rows[row][free];
+            }
+            direction
+        })
+        .collect();
+    Solution::Infinite {
+        particular,
+        directions,
+    }
+}
+
+/** Returned when the cheapest non-negative integer solution cannot be found */
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LinalgError {
+    /** Only systems with at most one free variable are supported */
+    TooManyFreeVariables(usize),
+    /** There are solutions of arbitrarily low cost */
+    Unbounded,
+}
+
+impl Display for LinalgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LinalgError::TooManyFreeVariables(n) => {
+                write!(f, "system has {} free variables, at most 1 is supported", n)
+            }
+            LinalgError::Unbounded => write!(f, "cost is unbounded below"),
+        }
+    }
+}
+
+impl std::error::Error for LinalgError {}
+
+/**
+* The solution of `A x = b` in non-negative integers that minimises `cost . x`, or None if
+* there is no such solution. Systems with one free variable are solved by intersecting the
+* integrality congruences of every variable and then picking the cheapest end of the range
+* allowed by non-negativity.
+*/
+pub fn min_cost_solution(
+    a: &[Vec<i64>],
+    b: &[i64],
+    cost: &[i64],
+) -> Result<Option<Vec<i128>>, LinalgError> {
+    assert_eq!(
+        cost.len(),
+        a.first().map_or(0, |row| row.len()),
+        "Cost vector has the wrong length"
+    );
+    let (particular, direction) = match solve(a, b) {
+        Solution::None => return Ok(None),
+        Solution::Unique(x) => return Ok(to_non_negative_integers(&x)),
+        Solution::Infinite {
+            particular,
+            mut directions,
+        } => {
+            if directions.len() > 1 {
+                return Err(LinalgError::TooManyFreeVariables(directions.len()));
+            }
+            (
+                particular,
+                directions.pop().expect("At least one direction"),
+            )
+        }
+    };
+    // x = p + t d, where t is the value of the free variable, so t must be an integer.
+    // Collect the values of t that make every other variable an integer as t = r (mod m).
+    let (mut r, mut m) = (0, 1);
+    for (p, d) in particular.iter().zip(direction.iter()) {
+        let Some((r2, m2)) = integer_congruence(*p, *d) else {
+            return Ok(None);
+        };
+        let Some(merged) = crt(&[(r, m), (r2, m2)]) else {
+            return Ok(None);
+        };
+        (r, m) = merged;
+    }
+
+    // every x_i = p_i + t d_i must be >= 0
+    let mut lo: Option<i128> = None;
+    let mut hi: Option<i128> = None;
+    for (p, d) in particular.iter().zip(direction.iter()) {
+        match d.cmp(&Rational::ZERO) {
+            Ordering::Greater => lo = lo.max(Some((-*p / *d).ceil())),
+            Ordering::Less => {
+                let bound = (-*p / *d).floor();
+                hi = Some(hi.map_or(bound, |h| h.min(bound)));
+            }
+            Ordering::Equal if *p < Rational::ZERO => return Ok(None),
+            Ordering::Equal => (),
+        }
+    }
+
+    let slope = cost
+        .iter()
+        .zip(direction.iter())
+        .fold(Rational::ZERO, |acc, (c, d)| acc + Rational::from(*c) * *d);
+    let Some(t) = cheapest_free_value(lo, hi, (r, m), slope.cmp(&Rational::ZERO))? else {
+        return Ok(None);
+    };
+
+    let x: Vec<Rational> = particular
+        .iter()
+        .zip(direction.iter())
+        .map(|(p, d)| *p + Rational::from(t) * *d)
+        .collect();
+    Ok(to_non_negative_integers(&x))
+}
+
+/**
+* The value of the free variable `t = r (mod m)` in `lo..=hi` that minimises a cost changing
+* by `slope` per unit of `t`. With a zero slope every value costs the same, so any bounded end
+* will do, and only a cost that falls forever towards a missing bound is `Unbounded`.
+*/
+fn cheapest_free_value(
+    lo: Option<i128>,
+    hi: Option<i128>,
+    (r, m): (i128, i128),
+    slope: Ordering,
+) -> Result<Option<i128>, LinalgError> {
+    let lowest = lo.map(|lo| lo + (r - lo).rem_euclid(m));
+    let highest = hi.map(|hi| hi - (hi - r).rem_euclid(m));
+    let t = match slope {
+        Ordering::Greater => lowest.ok_or(LinalgError::Unbounded)?,
+        Ordering::Less => highest.ok_or(LinalgError::Unbounded)?,
+        Ordering::Equal => lowest.or(highest).unwrap_or(r),
+    };
+    let in_range = lo.is_none_or(|lo| t >= lo) && hi.is_none_or(|hi| t <= hi);
+    Ok(in_range.then_some(t))
+}
+
+fn to_non_negative_integers(x: &[Rational]) -> Option<Vec<i128>> {
+    x.iter()
+        .map(|v| v.to_integer().filter(|v| *v >= 0))
+        .collect()
+}
+
+/** The integers t for which p + t d is an integer, as t = r (mod m) */
+fn integer_congruence(p: Rational, d: Rational) -> Option<(i128, i128)> {
+    // multiply through by the common denominator l: t * alpha = beta (mod l)
+    let l = lcm(p.den as u128, d.den as u128) as i128;
+    let alpha = (d.num * (l / d.den)).rem_euclid(l);
+    let beta = (-p.num * (l / p.den)).rem_euclid(l);
+    let g = gcd(alpha as u128, l as u128) as i128;
+    if beta % g != 0 {
+        return None;
+    }
+    let modulus = l / g;
+    let (_, inv, _) = ext_gcd(alpha / g, modulus);
+    Some(((beta / g * inv).rem_euclid(modulus), modulus))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::linalg::*;
+
+    fn r(num: i128, den: i128) -> Rational {
+        Rational::new(num, den)
+    }
+
+    #[test]
+    fn test_rational() {
+        assert_eq!(r(2, -4), r(-1, 2));
+        assert_eq!(r(1, 3) + r(1, 6), r(1, 2));
+        assert_eq!(r(-7, 2).floor(), -4);
+        assert_eq!(r(-7, 2).ceil(), -3);
+        assert!(r(1, 3) < r(1, 2));
+        assert_eq!(r(6, 3).to_integer(), Some(2));
+        assert_eq!(r(-1, 2).to_string(), "-1/2");
+        assert_eq!(r(1, 2).checked_mul(r(2, 3)), Some(r(1, 3)));
+        assert_eq!(r(i128::MAX, 1).checked_add(Rational::ONE), None);
+        assert_eq!(r(1, i128::MAX).checked_mul(r(1, 2)), None);
+    }
+
+    #[test]
+    fn test_solve() {
+        let a = vec![vec![94, 22], vec![34, 67]];
+        assert_eq!(
+            solve(&a, &[8400, 5400]),
+            Solution::Unique(vec![r(80, 1), r(40, 1)])
+        );
+
+        let collinear = vec![vec![1, 2], vec![2, 4]];
+        assert_eq!(solve(&collinear, &[3, 7]), Solution::None);
+        assert_eq!(
+            solve(&collinear, &[3, 6]),
+            Solution::Infinite {
+                particular: vec![r(3, 1), Rational::ZERO],
+                directions: vec![vec![r(-2, 1), Rational::ONE]],
+            }
+        );
+    }
+
+    #[test]
+    fn test_min_cost_solution() {
+        let a = vec![vec![94, 22], vec![34, 67]];
+        assert_eq!(
+            min_cost_solution(&a, &[8400, 5400], &[3, 1]),
+            Ok(Some(vec![80, 40]))
+        );
+        let a = vec![vec![26, 67], vec![66, 21]];
+        assert_eq!(min_cost_solution(&a, &[12748, 12176], &[3, 1]), Ok(None));
+
+        // 4 a + 6 b = 50: a = 2, b = 7 and a = 11, b = 1 both work
+        let collinear = vec![vec![4, 6], vec![2, 3]];
+        assert_eq!(
+            min_cost_solution(&collinear, &[50, 25], &[3, 1]),
+            Ok(Some(vec![2, 7]))
+        );
+        assert_eq!(
+            min_cost_solution(&collinear, &[50, 25], &[1, 3]),
+            Ok(Some(vec![11, 1]))
+        );
+        // the cost does not change along the line, so either end is cheapest
+        assert_eq!(
+            min_cost_solution(&collinear, &[50, 25], &[2, 3]),
+            Ok(Some(vec![11, 1]))
+        );
+        assert_eq!(min_cost_solution(&collinear, &[51, 25], &[1, 1]), Ok(None));
+        assert_eq!(min_cost_solution(&collinear, &[5, 2], &[1, 1]), Ok(None));
+
+        // B does not move the claw, so it is never worth pressing
+        let zero_column = vec![vec![2, 0], vec![3, 0]];
+        assert_eq!(
+            min_cost_solution(&zero_column, &[4, 6], &[3, 1]),
+            Ok(Some(vec![2, 0]))
+        );
+        assert_eq!(min_cost_solution(&zero_column, &[4, 5], &[3, 1]), Ok(None));
+
+        let underdetermined = vec![vec![1, 1, 1]];
+        assert_eq!(
+            min_cost_solution(&underdetermined, &[3], &[1, 1, 1]),
+            Err(LinalgError::TooManyFreeVariables(2))
+        );
+    }
+
+    #[test]
+    fn test_cheapest_free_value() {
+        // t = 1 (mod 3), bounded above by 10 only
+        assert_eq!(
+            cheapest_free_value(None, Some(10), (1, 3), Ordering::Equal),
+            Ok(Some(10))
+        );
+        assert_eq!(
+            cheapest_free_value(None, Some(10), (1, 3), Ordering::Less),
+            Ok(Some(10))
+        );
+        assert_eq!(
+            cheapest_free_value(None, Some(10), (1, 3), Ordering::Greater),
+            Err(LinalgError::Unbounded)
+        );
+        assert_eq!(
+            cheapest_free_value(Some(2), None, (1, 3), Ordering::Equal),
+            Ok(Some(4))
+        );
+        assert_eq!(
+            cheapest_free_value(None, None, (1, 3), Ordering::Equal),
+            Ok(Some(1))
+        );
+        assert_eq!(
+            cheapest_free_value(Some(5), Some(6), (1, 3), Ordering::Greater),
+            Ok(None)
+        );
+    }
+}
@@ -0,0 +1,191 @@
+use std::iter;
+
+use crate::{Grid, Loc, Point, Vec2};
+
+/**
+* The points `origin + k * step` for every integer k, i.e. a line through lattice points.
+* Build it with `Line::through` to visit every lattice point on the line, or with
+* `Line::spaced` to keep the spacing of the two points it was built from.
+*/
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Line {
+    origin: Point,
+    step: Vec2<i64>,
+}
+
+impl Line {
+    /** The line through `a` and `b` in steps of `b - a`, so that `at(0) == a` and `at(1) == b` */
+    pub fn spaced(a: Point, b: Point) -> Self {
+        assert!(a != b, "Line through {:?} and itself", a);
+        Line {
+            origin: a,
+            step: b - a,
+        }
+    }
+
+    /** The line through `a` and `b` in steps of the smallest lattice vector along it */
+    pub fn through(a: Point, b: Point) -> Self {
+        let mut line = Line::spaced(a, b);
+        line.step = line.step.normalize();
+        line
+    }
+
+    pub fn origin(&self) -> Point {
+        self.origin
+    }
+
+    /** Offset between consecutive points, with x the column and y the row */
+    pub fn step(&self) -> Vec2<i64> {
+        self.step
+    }
+
+    pub fn at(&self, k: i64) -> Point {
+        self.origin + self.step * k
+    }
+
+    /** The points for k = 0, 1, 2, ... */
+    pub fn forward(&self) -> impl Iterator<Item = Point> {
+        let step = self.step;
+        iter::successors(Some(self.origin), move |p| p.checked_add(step))
+    }
+
+    /** The points for k = 0, -1, -2, ... */
+    pub fn backward(&self) -> impl Iterator<Item = Point> {
+        let step = -self.step;
+        iter::successors(Some(self.origin), move |p| p.checked_add(step))
+    }
+
+    /** The range of k for which `at(k)` lies in an `n_rows` x `n_cols` grid */
+    pub fn k_range(&self, n_rows: usize, n_cols: usize) -> Option<(i64, i64)> {
+        let (lo_r, hi_r) = axis_range(self.origin.row, self.step.y, n_rows as i64)?;
+        let (lo_c, hi_c) = axis_range(self.origin.col, self.step.x, n_cols as i64)?;
+        let (lo, hi) = (lo_r.max(lo_c), hi_r.min(hi_c));
+        (lo <= hi).then_some((lo, hi))
+    }
+
+    /** Every point of the line inside `grid`, in order of increasing k */
+    pub fn clipped<T>(&self, grid: &Grid<T>) -> impl Iterator<Item = Loc> {
+        let line = *self;
+        let (n_rows, n_cols) = (grid.n_rows(), grid.n_cols());
+        self.k_range(n_rows, n_cols)
+            .into_iter()
+            .flat_map(|(lo, hi)| lo..=hi)
+            .map(move |k| {
+                line.at(k)
+                    .to_loc(n_rows, n_cols)
+                    .expect("k is in range of the grid")
+            })
+    }
+}
+
+/** The k with 0 <= origin + k * step < n, as an inclusive range */
+fn axis_range(origin: i64, step: i64, n: i64) -> Option<(i64, i64)> {
+    if step == 0 {
+        return (0..n).contains(&origin).then_some((i64::MIN, i64::MAX));
+    }
+    // normalise to a positive step
+    let (origin, step, lo, hi) = if step > 0 {
+        (origin, step, 0, n - 1)
+    } else {
+        (-origin, -step, 1 - n, 0)
+    };
+    let first = -((origin - lo).div_euclid(step));
+    let last = (hi - origin).div_euclid(step);
+    Some((first, last))
+}
+
+/** Every lattice point on the segment from `a` to `b`, both included */
+pub fn segment(a: Point, b: Point) -> impl Iterator<Item = Point> {
+    let (step, count) = if a == b {
+        (Vec2::new(0, 0), 0)
+    } else {
+        let step = (b - a).normalize();
+        let count = (b - a).chebyshev() / step.chebyshev();
+        (step, count)
+    };
+    (0..=count).map(move |k| a + step * k)
+}
+
+/**
+* The cells a straight line from `a` to `b` passes through, by Bresenham's algorithm.
+* Unlike `segment` this also works when the line hits few or no other lattice points.
+*/
+pub fn bresenham(a: Point, b: Point) -> impl Iterator<Item = Point> {
+    let dc = (b.col - a.col).abs();
+    let dr = -(b.row - a.row).abs();
+    let sc = (b.col - a.col).signum();
+    let sr = (b.row - a.row).signum();
+    let mut err = dc + dr;
+    let mut next = Some(a);
+    iter::from_fn(move || {
+        let curr = next?;
+        next = (curr != b).then(|| {
+            let mut p = curr;
+            let e2 = 2 * err;
+            if e2 >= dr {
+                err += dr;
+                p.col += sc;
+            }
+            if e2 <= dc {
+                err += dc;
+                p.row += sr;
+            }
+            p
+        });
+        Some(curr)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn p(row: i64, col: i64) -> Point {
+        Point::new(row, col)
+    }
+
+    #[test]
+    fn test_line() {
+        let spaced = Line::spaced(p(3, 4), p(5, 5));
+        assert_eq!(spaced.at(-1), p(1, 3));
+        assert_eq!(spaced.at(2), p(7, 6));
+        assert_eq!(Line::through(p(0, 0), p(4, 6)).step(), Vec2::new(3, 2));
+        assert_eq!(
+            spaced.backward().take(3).collect::<Vec<_>>(),
+            vec![p(3, 4), p(1, 3), p(-1, 2)]
+        );
+    }
+
+    #[test]
+    fn test_clipped() {
+        let grid = Grid::new(10, 10, 0);
+        let line = Line::spaced(p(3, 4), p(5, 5));
+        assert_eq!(
+            line.clipped(&grid).collect::<Vec<_>>(),
+            vec![Loc(1, 3), Loc(3, 4), Loc(5, 5), Loc(7, 6), Loc(9, 7)]
+        );
+        let horizontal = Line::through(p(2, -5), p(2, 20));
+        assert_eq!(horizontal.clipped(&grid).count(), 10);
+        let outside = Line::through(p(-1, 0), p(-1, 1));
+        assert_eq!(outside.clipped(&grid).count(), 0);
+        let anti_diagonal = Line::through(p(0, 9), p(1, 8));
+        assert_eq!(anti_diagonal.clipped(&grid).count(), 10);
+    }
+
+    #[test]
+    fn test_segment_and_bresenham() {
+        assert_eq!(
+            segment(p(0, 0), p(4, 6)).collect::<Vec<_>>(),
+            vec![p(0, 0), p(2, 3), p(4, 6)]
+        );
+        assert_eq!(segment(p(1, 1), p(1, 1)).count(), 1);
+        assert_eq!(
+            bresenham(p(0, 0), p(1, 3)).collect::<Vec<_>>(),
+            vec![p(0, 0), p(0, 1), p(1, 2), p(1, 3)]
+        );
+        assert_eq!(
+            bresenham(p(2, 2), p(-1, -1)).collect::<Vec<_>>(),
+            vec![p(2, 2), p(1, 1), p(0, 0), p(-1, -1)]
+        );
+    }
+}
@@ -1,14 +1,167 @@
-use crate::Vec2;
-pub type Loc = (usize, usize);
+use std::fmt::Debug;
+use std::ops::{Add, AddAssign, Sub};
+
+use crate::{Dir, Vec2};
+/**
+* A grid index as (row, col), the convention `Grid` uses everywhere.
+* A distinct type rather than a tuple, so it cannot be mixed up with an (x, y) pair.
+*/
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Loc(pub usize, pub usize);
 
 pub fn euclidean_distance(a: Loc, b: Loc) -> f64 {
-    let (a1, a2) = a;
-    let (b1, b2) = b;
-    let a = Vec2::new(a1 as i32, a2 as i32);
-    let b = Vec2::new(b1 as i32, b2 as i32);
-    (a - b).magnitude()
+    let dr = a.0.abs_diff(b.0) as f64;
+    let dc = a.1.abs_diff(b.1) as f64;
+    (dr * dr + dc * dc).sqrt()
 }
 
 pub fn manhattan_distance(a: &Loc, b: &Loc) -> usize {
     a.0.abs_diff(b.0) + a.1.abs_diff(b.1)
 }
+
+/**
+* A grid position with signed components, so that it can step off the edge of a grid
+* without underflowing. Ordered row-major, like `Loc`.
+* Only turns into a `Loc` once it is known to be inside a grid.
+*/
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+    pub row: i64,
+    pub col: i64,
+}
+
+impl Point {
+    pub fn new(row: i64, col: i64) -> Self {
+        Point { row, col }
+    }
+
+    /** The neighbouring point in direction `dir`, or None on overflow */
+    pub fn checked_step(&self, dir: Dir) -> Option<Point> {
+        let (dr, dc) = dir.delta();
+        self.checked_add(Vec2::new(dc as i64, dr as i64))
+    }
+
+    /** Adds an offset whose x is the column and y is the row, or None on overflow */
+    pub fn checked_add(&self, offset: Vec2<i64>) -> Option<Point> {
+        Some(Point {
+            row: self.row.checked_add(offset.y)?,
+            col: self.col.checked_add(offset.x)?,
+        })
+    }
+
+    /** The grid index of this point, if it lies in an `n_rows` x `n_cols` grid */
+    pub fn to_loc(&self, n_rows: usize, n_cols: usize) -> Option<Loc> {
+        let r = usize::try_from(self.row).ok()?;
+        let c = usize::try_from(self.col).ok()?;
+        (r < n_rows && c < n_cols).then_some(Loc(r, c))
+    }
+}
+
+/** Panics above i64::MAX, which is more elements than any `Vec` can hold */
+impl From<Loc> for Point {
+    fn from(value: Loc) -> Self {
+        value.to_point().expect("Loc does not fit in a Point")
+    }
+}
+
+/** Fails for points with a negative component */
+impl TryFrom<Point> for Loc {
+    type Error = Point;
+
+    fn try_from(value: Point) -> Result<Self, Self::Error> {
+        match (usize::try_from(value.row), usize::try_from(value.col)) {
+            (Ok(r), Ok(c)) => Ok(Loc(r, c)),
+            _ => Err(value),
+        }
+    }
+}
+
+impl Add<Dir> for Point {
+    type Output = Point;
+
+    fn add(self, rhs: Dir) -> Self::Output {
+        self.checked_step(rhs).expect("Point overflowed")
+    }
+}
+
+impl Add<Vec2<i64>> for Point {
+    type Output = Point;
+
+    fn add(self, rhs: Vec2<i64>) -> Self::Output {
+        self.checked_add(rhs).expect("Point overflowed")
+    }
+}
+
+impl AddAssign<Dir> for Point {
+    fn add_assign(&mut self, rhs: Dir) {
+        *self = *self + rhs;
+    }
+}
+
+impl AddAssign<Vec2<i64>> for Point {
+    fn add_assign(&mut self, rhs: Vec2<i64>) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub<Point> for Point {
+    type Output = Vec2<i64>;
+
+    /** The offset from `rhs` to `self`, with x the column and y the row */
+    fn sub(self, rhs: Point) -> Self::Output {
+        Vec2::new(self.col - rhs.col, self.row - rhs.row)
+    }
+}
+
+/**
+* Anything that can index a `Grid`: a `Loc`, a `Point`, a `Vec2`, or a reference to one
+*/
+pub trait GridIndex: Debug {
+    /** None if the index cannot be represented as a `Point`, and so is never in bounds */
+    fn to_point(&self) -> Option<Point>;
+}
+
+impl GridIndex for Loc {
+    fn to_point(&self) -> Option<Point> {
+        Some(Point::new(
+            i64::try_from(self.0).ok()?,
+            i64::try_from(self.1).ok()?,
+        ))
+    }
+}
+
+impl GridIndex for Point {
+    fn to_point(&self) -> Option<Point> {
+        Some(*self)
+    }
+}
+
+impl<I: GridIndex> GridIndex for &I {
+    fn to_point(&self) -> Option<Point> {
+        (*self).to_point()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_point_arithmetic() {
+        let p = Point::new(0, 2);
+        assert_eq!(p + Dir::NW, Point::new(-1, 1));
+        assert_eq!(p + Vec2::new(-3, 4), Point::new(4, -1));
+        assert_eq!(Point::new(4, -1) - p, Vec2::new(-3, 4));
+        assert_eq!(Point::new(i64::MAX, 0).checked_step(Dir::S), None);
+        assert!(Point::new(0, 5) < Point::new(1, 0));
+    }
+
+    #[test]
+    fn test_to_loc() {
+        assert_eq!(Point::new(2, 3).to_loc(3, 4), Some(Loc(2, 3)));
+        assert_eq!(Point::new(2, 4).to_loc(3, 4), None);
+        assert_eq!(Point::new(-1, 0).to_loc(3, 4), None);
+        assert_eq!(Loc::try_from(Point::new(0, -1)), Err(Point::new(0, -1)));
+        assert_eq!(Loc::try_from(Point::from(Loc(5, 6))), Ok(Loc(5, 6)));
+    }
+}
@@ -0,0 +1,174 @@
+use std::ops::{Div, Mul, Rem};
+
+/** The unsigned integer types `gcd` and `lcm` work on */
+pub trait Unsigned:
+    Copy + Eq + Rem<Output = Self> + Div<Output = Self> + Mul<Output = Self>
+{
+    const ZERO: Self;
+}
+
+macro_rules! impl_unsigned {
+    ($($t:ty),*) => {
+        $(
+            impl Unsigned for $t {
+                const ZERO: Self = 0;
+            }
+        )*
+    };
+}
+
+impl_unsigned!(u8, u16, u32, u64, u128, usize);
+
+/** Greatest common divisor, with gcd(0, 0) = 0 */
+pub fn gcd<T: Unsigned>(a: T, b: T) -> T {
+    if b == T::ZERO {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/** Least common multiple, with lcm(0, n) = 0 */
+pub fn lcm<T: Unsigned>(a: T, b: T) -> T {
+    if a == T::ZERO || b == T::ZERO {
+        T::ZERO
+    } else {
+        a / gcd(a, b) * b
+    }
+}
+
+/** (g, x, y) with a x + b y = g = gcd(a, b) */
+pub fn ext_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        (a, 1, 0)
+    } else {
+        let (g, x, y) = ext_gcd(b, a % b);
+        (g, y, x - (a / b) * y)
+    }
+}
+
+/** The inverse of `a` modulo `m` in `0..m`, if `a` and `m` are coprime */
+pub fn mod_inv(a: i128, m: i128) -> Option<i128> {
+    let (g, x, _) = ext_gcd(a.rem_euclid(m), m);
+    (g == 1).then(|| x.rem_euclid(m))
+}
+
+/** `base` to the power `exp` modulo `m`, by repeated squaring */
+pub fn mod_pow(base: u64, mut exp: u64, m: u64) -> u64 {
+    let m = m as u128;
+    let mut base = base as u128 % m;
+    let mut result = 1 % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % m;
+        }
+        base = base * base % m;
+        exp >>= 1;
+    }
+    result as u64
+}
+
+/**
+* Chinese Remainder Theorem: the x with x = r (mod m) for every (r, m) in `congruences`,
+* as (x, lcm of the moduli) with x in `0..lcm`. The moduli do not need to be coprime;
+* returns None if the congruences contradict each other.
+*/
+pub fn crt(congruences: &[(i128, i128)]) -> Option<(i128, i128)> {
+    congruences.iter().try_fold((0, 1), |(r1, m1), &(r2, m2)| {
+        let (g, p, _) = ext_gcd(m1, m2);
+        if (r2 - r1) % g != 0 {
+            return None;
+        }
+        let modulus = m1 / g * m2;
+        let k = ((r2 - r1) / g * p).rem_euclid(m2 / g);
+        Some(((r1 + m1 * k).rem_euclid(modulus), modulus))
+    })
+}
+
+/** Decimal digit operations, checked so that they never overflow silently */
+pub trait Digits: Sized {
+    /** Number of decimal digits, where 0 has one digit */
+    fn digit_count(self) -> u32;
+
+    /** Splits off the lowest `low_digits` digits, e.g. 123456 at 2 gives (1234, 56) */
+    fn split_digits(self, low_digits: u32) -> (Self, Self);
+
+    /** Writes `other` after `self`, e.g. 12 and 345 give 12345, or None on overflow */
+    fn concat_digits(self, other: Self) -> Option<Self>;
+}
+
+macro_rules! impl_digits {
+    ($($t:ty),*) => {
+        $(
+            impl Digits for $t {
+                fn digit_count(self) -> u32 {
+                    self.checked_ilog10().map_or(1, |d| d + 1)
+                }
+
+                fn split_digits(self, low_digits: u32) -> (Self, Self) {
+                    match (10 as $t).checked_pow(low_digits) {
+                        Some(p) => (self / p, self % p),
+                        // more digits than the type can hold, so all of them are low
+                        None => (0, self),
+                    }
+                }
+
+                fn concat_digits(self, other: Self) -> Option<Self> {
+                    (10 as $t)
+                        .checked_pow(other.digit_count())?
+                        .checked_mul(self)?
+                        .checked_add(other)
+                }
+            }
+        )*
+    };
+}
+
+impl_digits!(u64, u128);
+
+#[cfg(test)]
+mod tests {
+    use crate::math::*;
+
+    #[test]
+    fn test_gcd() {
+        assert_eq!(gcd(12u64, 18), 6);
+        assert_eq!(gcd(0u32, 7), 7);
+        assert_eq!(lcm(4u128, 6), 12);
+        assert_eq!(lcm(0u8, 6), 0);
+        let (g, x, y) = ext_gcd(240, 46);
+        assert_eq!(g, 2);
+        assert_eq!(240 * x + 46 * y, 2);
+    }
+
+    #[test]
+    fn test_modular() {
+        assert_eq!(mod_inv(3, 11), Some(4));
+        assert_eq!(mod_inv(-3, 11), Some(7));
+        assert_eq!(mod_inv(4, 8), None);
+        assert_eq!(mod_pow(2, 10, 1000), 24);
+        assert_eq!(mod_pow(u64::MAX, u64::MAX, 1_000_000_007), 254_368_884);
+        assert_eq!(mod_pow(5, 0, 1), 0);
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(1, 4), (3, 6)]), Some((9, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[]), Some((0, 1)));
+    }
+
+    #[test]
+    fn test_digits() {
+        assert_eq!(0u64.digit_count(), 1);
+        assert_eq!(u64::MAX.digit_count(), 20);
+        assert_eq!(10_000_000_000u64.digit_count(), 11);
+        assert_eq!(123456u64.split_digits(2), (1234, 56));
+        assert_eq!(u64::MAX.split_digits(10), (1844674407, 3709551615));
+        assert_eq!(u64::MAX.split_digits(20), (0, u64::MAX));
+        assert_eq!(12u64.concat_digits(345), Some(12345));
+        assert_eq!(12u64.concat_digits(0), Some(120));
+        assert_eq!(u64::MAX.concat_digits(1), None);
+        assert_eq!(
+            (u64::MAX as u128).concat_digits(1),
+            Some(184467440737095516151)
+        );
+    }
+}
@@ -0,0 +1,227 @@
+use crate::{Grid, Point};
+
+/**
+* An axis-aligned rectangle of grid cells, from `top_left` up to but excluding
+* `bottom_right`. Signed, so it can describe regions that hang off a grid.
+*/
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Rect {
+    top_left: Point,
+    bottom_right: Point,
+}
+
+/** Whether the middle row and column of an odd-sized `Rect` belong to its quadrants */
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Midlines {
+    /** Drop the middle row and column, as when counting robots per quadrant */
+    Exclude,
+    /** Put the middle row and column in both neighbouring quadrants */
+    Include,
+}
+
+impl Rect {
+    /** Panics if the corners are the wrong way round */
+    pub fn new(top_left: Point, bottom_right: Point) -> Self {
+        assert!(
+            top_left.row <= bottom_right.row && top_left.col <= bottom_right.col,
+            "Rect corners {:?} and {:?} are the wrong way round",
+            top_left,
+            bottom_right
+        );
+        Rect {
+            top_left,
+            bottom_right,
+        }
+    }
+
+    /** The rectangle with its top left corner at (0, 0) */
+    pub fn with_size(n_rows: i64, n_cols: i64) -> Self {
+        Rect::new(Point::new(0, 0), Point::new(n_rows, n_cols))
+    }
+
+    /** The smallest rectangle containing every point, or None if there are none */
+    pub fn bounding<I: IntoIterator<Item = Point>>(points: I) -> Option<Self> {
+        points
+            .into_iter()
+            .map(|p| Rect::new(p, Point::new(p.row + 1, p.col + 1)))
+            .reduce(|a, b| a.union(&b))
+    }
+
+    pub fn top_left(&self) -> Point {
+        self.top_left
+    }
+
+    /** The corner just outside the rectangle */
+    pub fn bottom_right(&self) -> Point {
+        self.bottom_right
+    }
+
+    pub fn n_rows(&self) -> i64 {
+        self.bottom_right.row - self.top_left.row
+    }
+
+    pub fn n_cols(&self) -> i64 {
+        self.bottom_right.col - self.top_left.col
+    }
+
+    pub fn area(&self) -> u64 {
+        (self.n_rows() * self.n_cols()) as u64
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.area() == 0
+    }
+
+    pub fn contains(&self, p: &Point) -> bool {
+        (self.top_left.row..self.bottom_right.row).contains(&p.row)
+            && (self.top_left.col..self.bottom_right.col).contains(&p.col)
+    }
+
+    /** The overlap of two rectangles, or None if they do not share any cell */
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        let top_left = Point::new(
+            self.top_left.row.max(other.top_left.row),
+            self.top_left.col.max(other.top_left.col),
+        );
+        let bottom_right = Point::new(
+            self.bottom_right.row.min(other.bottom_right.row),
+            self.bottom_right.col.min(other.bottom_right.col),
+        );
+        (top_left.row < bottom_right.row && top_left.col < bottom_right.col)
+            .then(|| Rect::new(top_left, bottom_right))
+    }
+
+    /** The smallest rectangle containing both */
+    pub fn union(&self, other: &Rect) -> Rect {
+        Rect::new(
+            Point::new(
+                self.top_left.row.min(other.top_left.row),
+                self.top_left.col.min(other.top_left.col),
+            ),
+            Point::new(
+                self.bottom_right.row.max(other.bottom_right.row),
+                self.bottom_right.col.max(other.bottom_right.col),
+            ),
+        )
+    }
+
+    /**
+     * Splits the rectangle into its NW, NE, SW and SE quadrants, in that order.
+     * For an even size the halves are exact and `midlines` makes no difference.
+     */
+    pub fn quadrants(&self, midlines: Midlines) -> [Rect; 4] {
+        let (rows_top, rows_bottom) = halves(self.top_left.row, self.n_rows(), midlines);
+        let (cols_left, cols_right) = halves(self.top_left.col, self.n_cols(), midlines);
+        [
+            (rows_top, cols_left),
+            (rows_top, cols_right),
+            (rows_bottom, cols_left),
+            (rows_bottom, cols_right),
+        ]
+        .map(|((top, bottom), (left, right))| {
+            Rect::new(Point::new(top, left), Point::new(bottom, right))
+        })
+    }
+
+    /**
+     * Covers the rectangle with `tile_rows` x `tile_cols` tiles in row-major order,
+     * starting from the top left. Tiles on the bottom and right edges are cut short.
+     */
+    pub fn tiles(&self, tile_rows: i64, tile_cols: i64) -> impl Iterator<Item = Rect> {
+        assert!(tile_rows > 0 && tile_cols > 0, "Tiles must not be empty");
+        let rect = *self;
+        (rect.top_left.row..rect.bottom_right.row)
+            .step_by(tile_rows as usize)
+            .flat_map(move |top| {
+                (rect.top_left.col..rect.bottom_right.col)
+                    .step_by(tile_cols as usize)
+                    .map(move |left| {
+                        let bottom_right = Point::new(
+                            (top + tile_rows).min(rect.bottom_right.row),
+                            (left + tile_cols).min(rect.bottom_right.col),
+                        );
+                        Rect::new(Point::new(top, left), bottom_right)
+                    })
+            })
+    }
+
+    /** Iterates over the cells in row-major order */
+    pub fn cells(&self) -> impl Iterator<Item = Point> {
+        let rect = *self;
+        (rect.top_left.row..rect.bottom_right.row).flat_map(move |row| {
+            (rect.top_left.col..rect.bottom_right.col).map(move |col| Point::new(row, col))
+        })
+    }
+}
+
+/** The two halves of `start..start + len` as (start, end) pairs */
+fn halves(start: i64, len: i64, midlines: Midlines) -> ((i64, i64), (i64, i64)) {
+    let half = len / 2;
+    let middle = len % 2;
+    match midlines {
+        Midlines::Exclude => ((start, start + half), (start + half + middle, start + len)),
+        Midlines::Include => ((start, start + half + middle), (start + half, start + len)),
+    }
+}
+
+impl<T> Grid<T> {
+    /** The cells of the grid, as a `Rect` from (0, 0) */
+    pub fn bounds(&self) -> Rect {
+        Rect::with_size(self.n_rows() as i64, self.n_cols() as i64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn p(row: i64, col: i64) -> Point {
+        Point::new(row, col)
+    }
+
+    #[test]
+    fn test_set_ops() {
+        let a = Rect::new(p(0, 0), p(4, 5));
+        let b = Rect::new(p(2, 3), p(6, 9));
+        assert_eq!(a.area(), 20);
+        assert!(a.contains(&p(3, 4)));
+        assert!(!a.contains(&p(4, 4)));
+        assert_eq!(a.intersection(&b), Some(Rect::new(p(2, 3), p(4, 5))));
+        assert_eq!(a.union(&b), Rect::new(p(0, 0), p(6, 9)));
+        assert_eq!(a.intersection(&Rect::new(p(4, 0), p(5, 5))), None);
+        assert_eq!(
+            Rect::bounding([p(3, -1), p(0, 2), p(1, 1)]),
+            Some(Rect::new(p(0, -1), p(4, 3)))
+        );
+        assert_eq!(Rect::bounding([]), None);
+    }
+
+    #[test]
+    fn test_quadrants() {
+        let room = Rect::with_size(7, 11);
+        let [nw, ne, sw, se] = room.quadrants(Midlines::Exclude);
+        assert_eq!(nw, Rect::new(p(0, 0), p(3, 5)));
+        assert_eq!(se, Rect::new(p(4, 6), p(7, 11)));
+        assert!(!ne.contains(&p(3, 8)) && !sw.contains(&p(3, 2)));
+
+        let [nw, _, _, se] = room.quadrants(Midlines::Include);
+        assert!(nw.contains(&p(3, 5)) && se.contains(&p(3, 5)));
+
+        let even = Rect::with_size(4, 4).quadrants(Midlines::Exclude);
+        assert_eq!(even.iter().map(|q| q.area()).sum::<u64>(), 16);
+    }
+
+    #[test]
+    fn test_tiles_and_cells() {
+        let rect = Rect::new(p(1, 1), p(4, 6));
+        let tiles = rect.tiles(2, 2).collect::<Vec<_>>();
+        assert_eq!(tiles.len(), 6);
+        assert_eq!(tiles[5], Rect::new(p(3, 5), p(4, 6)));
+        assert_eq!(tiles.iter().map(|t| t.area()).sum::<u64>(), rect.area());
+        assert_eq!(
+            Rect::new(p(0, 0), p(2, 2)).cells().collect::<Vec<_>>(),
+            vec![p(0, 0), p(0, 1), p(1, 0), p(1, 1)]
+        );
+        assert_eq!(Grid::new(3, 4, '.').bounds(), Rect::with_size(3, 4));
+    }
+}
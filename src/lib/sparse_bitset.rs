@@ -0,0 +1,639 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Display};
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Sub, SubAssign};
+
+use crate::bitset::{fmt_values, BitSetIter, BitSetOps};
+
+/** Containers with more values than this are stored as bitmaps */
+const ARRAY_MAX: usize = 4096;
+const BITMAP_WORDS: usize = 1024;
+
+/**
+* Stores a set of u64 values in chunks of 2^16, so that large or scattered values stay cheap.
+*
+* Each chunk is a sorted array while it is sparse, and a bitmap once it is dense, in the style
+* of roaring bitmaps. Chunks are kept in their canonical form, so equal sets are structurally equal.
+*/
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SparseBitSet {
+    chunks: BTreeMap<u64, Container>,
+    count: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum Container {
+    Array(Vec<u16>),
+    Bitmap(Box<[u64; BITMAP_WORDS]>, usize),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum SetOp {
+    Union,
+    Intersection,
+    Difference,
+    SymmetricDifference,
+}
+
+impl SetOp {
+    fn apply(&self, lhs: u64, rhs: u64) -> u64 {
+        match self {
+            SetOp::Union => lhs | rhs,
+            SetOp::Intersection => lhs & rhs,
+            SetOp::Difference => lhs & !rhs,
+            SetOp::SymmetricDifference => lhs ^ rhs,
+        }
+    }
+
+    /** whether a value in the left and/or right operand is in the result */
+    fn keeps(&self, in_lhs: bool, in_rhs: bool) -> bool {
+        self.apply(in_lhs as u64, in_rhs as u64) & 1 == 1
+    }
+}
+
+/** Merges two sorted arrays, keeping the values that `op` keeps */
+fn merge_sorted(a: &[u16], b: &[u16], op: SetOp) -> Vec<u16> {
+    let mut result = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        let (value, in_a, in_b) = match (a.get(i), b.get(j)) {
+            (Some(x), Some(y)) if x == y => (*x, true, true),
+            (Some(x), Some(y)) if x < y => (*x, true, false),
+            (Some(x), None) => (*x, true, false),
+            (_, Some(y)) => (*y, false, true),
+            (None, None) => unreachable!(),
+        };
+        i += in_a as usize;
+        j += in_b as usize;
+        if op.keeps(in_a, in_b) {
+            result.push(value);
+        }
+    }
+    result
+}
+
+fn split(value: u64) -> (u64, u16) {
+    (value >> 16, value as u16)
+}
+
+impl Container {
+    fn len(&self) -> usize {
+        match self {
+            Container::Array(values) => values.len(),
+            Container::Bitmap(_, count) => *count,
+        }
+    }
+
+    fn contains(&self, low: u16) -> bool {
+        match self {
+            Container::Array(values) => values.binary_search(&low).is_ok(),
+            Container::Bitmap(words, _) => words[low as usize / 64] & (1 << (low % 64)) != 0,
+        }
+    }
+
+    /** returns true if the value was not already present */
+    fn insert(&mut self, low: u16) -> bool {
+        let inserted = match self {
+            Container::Array(values) => match values.binary_search(&low) {
+                Ok(_) => false,
+                Err(idx) => {
+                    values.insert(idx, low);
+                    true
+                }
+            },
+            Container::Bitmap(words, count) => {
+                let (word, mask) = (low as usize / 64, 1u64 << (low % 64));
+                let inserted = words[word] & mask == 0;
+                words[word] |= mask;
+                *count += inserted as usize;
+                inserted
+            }
+        };
+        self.normalize();
+        inserted
+    }
+
+    /** returns true if the value was present */
+    fn remove(&mut self, low: u16) -> bool {
+        let removed = match self {
+            Container::Array(values) => match values.binary_search(&low) {
+                Ok(idx) => {
+                    values.remove(idx);
+                    true
+                }
+                Err(_) => false,
+            },
+            Container::Bitmap(words, count) => {
+                let (word, mask) = (low as usize / 64, 1u64 << (low % 64));
+                let removed = words[word] & mask != 0;
+                words[word] &= !mask;
+                *count -= removed as usize;
+                removed
+            }
+        };
+        self.normalize();
+        removed
+    }
+
+    fn to_words(&self) -> Box<[u64; BITMAP_WORDS]> {
+        match self {
+            Container::Array(values) => {
+                let mut words = Box::new([0; BITMAP_WORDS]);
+                for low in values {
+                    words[*low as usize / 64] |= 1 << (low % 64);
+                }
+                words
+            }
+            Container::Bitmap(words, _) => words.clone(),
+        }
+    }
+
+    fn from_words(words: Box<[u64; BITMAP_WORDS]>) -> Self {
+        let count = words.iter().map(|w| w.count_ones() as usize).sum();
+        let mut container = Container::Bitmap(words, count);
+        container.normalize();
+        container
+    }
+
+    /** switches between array and bitmap storage depending on the number of values */
+    fn normalize(&mut self) {
+        match self {
+            Container::Array(values) if values.len() > ARRAY_MAX => {
+                *self = Container::from_words(Container::Array(std::mem::take(values)).to_words());
+            }
+            Container::Bitmap(words, count) if *count <= ARRAY_MAX => {
+                let values = BitSetIter::new(&words[..]).map(|v| v as u16).collect();
+                *self = Container::Array(values);
+            }
+            _ => (),
+        }
+    }
+
+    /**
+     * Combines two containers without expanding arrays into bitmaps unless the result
+     * needs one: two arrays are merged, and an array is either filtered through a bitmap or
+     * applied to a copy of it, whichever result can be the larger.
+     */
+    fn combine(&self, other: &Container, op: SetOp) -> Container {
+        use Container::*;
+        let mut result = match (self, other) {
+            (Array(a), Array(b)) => Array(merge_sorted(a, b, op)),
+            (Bitmap(a, _), Bitmap(b, _)) => {
+                let mut words = a.clone();
+                for (l, r) in words.iter_mut().zip(b.iter()) {
+                    *l = op.apply(*l, *r);
+                }
+                Container::from_words(words)
+            }
+            (Array(a), Bitmap(..)) if matches!(op, SetOp::Intersection | SetOp::Difference) => {
+                Array(
+                    a.iter()
+                        .copied()
+                        .filter(|v| op.keeps(true, other.contains(*v)))
+                        .collect(),
+                )
+            }
+            (Bitmap(..), Array(b)) if op == SetOp::Intersection => {
+                Array(b.iter().copied().filter(|v| self.contains(*v)).collect())
+            }
+            // values outside the array keep their bit from the bitmap
+            (Array(values), Bitmap(words, _)) | (Bitmap(words, _), Array(values)) => {
+                let array_is_lhs = matches!(self, Array(_));
+                let mut words = words.clone();
+                for low in values {
+                    let (word, mask) = (*low as usize / 64, 1u64 << (low % 64));
+                    let in_bitmap = words[word] & mask != 0;
+                    let keep = if array_is_lhs {
+                        op.keeps(true, in_bitmap)
+                    } else {
+                        op.keeps(in_bitmap, true)
+                    };
+                    if keep {
+                        words[word] |= mask;
+                    } else {
+                        words[word] &= !mask;
+                    }
+                }
+                Container::from_words(words)
+            }
+        };
+        result.normalize();
+        result
+    }
+
+    fn intersection_len(&self, other: &Container) -> usize {
+        match (self, other) {
+            (Container::Array(values), other) | (other, Container::Array(values)) => {
+                values.iter().filter(|v| other.contains(**v)).count()
+            }
+            (Container::Bitmap(a, _), Container::Bitmap(b, _)) => a
+                .iter()
+                .zip(b.iter())
+                .map(|(x, y)| (x & y).count_ones() as usize)
+                .sum(),
+        }
+    }
+
+    fn iter(&self) -> ContainerIter<'_> {
+        match self {
+            Container::Array(values) => ContainerIter::Array(values.iter()),
+            Container::Bitmap(words, _) => ContainerIter::Bitmap(BitSetIter::new(&words[..])),
+        }
+    }
+}
+
+enum ContainerIter<'a> {
+    Array(std::slice::Iter<'a, u16>),
+    Bitmap(BitSetIter<'a>),
+}
+
+impl Iterator for ContainerIter<'_> {
+    type Item = u16;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            ContainerIter::Array(it) => it.next().copied(),
+            ContainerIter::Bitmap(it) => it.next().map(|v| v as u16),
+        }
+    }
+}
+
+impl SparseBitSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    pub fn insert(&mut self, value: u64) {
+        let (high, low) = split(value);
+        let inserted = self
+            .chunks
+            .entry(high)
+            .or_insert_with(|| Container::Array(Vec::new()))
+            .insert(low);
+        self.count += inserted as usize;
+    }
+
+    pub fn remove(&mut self, value: u64) {
+        let (high, low) = split(value);
+        if let Some(container) = self.chunks.get_mut(&high) {
+            if container.remove(low) {
+                self.count -= 1;
+                if container.len() == 0 {
+                    self.chunks.remove(&high);
+                }
+            }
+        }
+    }
+
+    pub fn contains(&self, value: u64) -> bool {
+        let (high, low) = split(value);
+        self.chunks.get(&high).is_some_and(|c| c.contains(low))
+    }
+
+    pub fn iter(&self) -> SparseBitSetIter<'_> {
+        SparseBitSetIter {
+            chunks: self.chunks.iter(),
+            current: None,
+        }
+    }
+
+    /** applies `op` to every chunk key present in either set, dropping chunks that end up empty */
+    fn merge_with<F>(&mut self, other: &SparseBitSet, op: F)
+    where
+        F: Fn(Option<&Container>, Option<&Container>) -> Option<Container>,
+    {
+        let keys: BTreeSet<u64> = self
+            .chunks
+            .keys()
+            .chain(other.chunks.keys())
+            .copied()
+            .collect();
+        for key in keys {
+            match op(self.chunks.get(&key), other.chunks.get(&key)) {
+                Some(c) if c.len() > 0 => {
+                    self.chunks.insert(key, c);
+                }
+                _ => {
+                    self.chunks.remove(&key);
+                }
+            }
+        }
+        self.count = self.chunks.values().map(|c| c.len()).sum();
+    }
+
+    pub fn union_inplace(&mut self, other: &SparseBitSet) {
+        self.merge_with(other, |a, b| match (a, b) {
+            (Some(a), Some(b)) => Some(a.combine(b, SetOp::Union)),
+            (a, b) => a.or(b).cloned(),
+        });
+    }
+
+    pub fn intersection_inplace(&mut self, other: &SparseBitSet) {
+        self.merge_with(other, |a, b| match (a, b) {
+            (Some(a), Some(b)) => Some(a.combine(b, SetOp::Intersection)),
+            _ => None,
+        });
+    }
+
+    pub fn difference_inplace(&mut self, other: &SparseBitSet) {
+        self.merge_with(other, |a, b| match (a, b) {
+            (Some(a), Some(b)) => Some(a.combine(b, SetOp::Difference)),
+            (a, _) => a.cloned(),
+        });
+    }
+
+    pub fn symmetric_difference_inplace(&mut self, other: &SparseBitSet) {
+        self.merge_with(other, |a, b| match (a, b) {
+            (Some(a), Some(b)) => Some(a.combine(b, SetOp::SymmetricDifference)),
+            (a, b) => a.or(b).cloned(),
+        });
+    }
+
+    /** Size of the intersection, without building it */
+    pub fn intersection_len(&self, other: &SparseBitSet) -> usize {
+        self.chunks
+            .iter()
+            .filter_map(|(key, a)| other.chunks.get(key).map(|b| a.intersection_len(b)))
+            .sum()
+    }
+
+    /** true if the two sets share no values */
+    pub fn is_disjoint(&self, other: &SparseBitSet) -> bool {
+        self.intersection_len(other) == 0
+    }
+}
+
+impl BitSetOps for SparseBitSet {
+    type Iter<'a> = SparseBitSetIter<'a>;
+
+    fn insert(&mut self, value: u64) {
+        SparseBitSet::insert(self, value)
+    }
+
+    fn remove(&mut self, value: u64) {
+        SparseBitSet::remove(self, value)
+    }
+
+    fn contains(&self, value: u64) -> bool {
+        SparseBitSet::contains(self, value)
+    }
+
+    fn len(&self) -> usize {
+        SparseBitSet::len(self)
+    }
+
+    fn iter(&self) -> SparseBitSetIter<'_> {
+        SparseBitSet::iter(self)
+    }
+
+    fn union_inplace(&mut self, other: &Self) {
+        SparseBitSet::union_inplace(self, other)
+    }
+
+    fn intersection_inplace(&mut self, other: &Self) {
+        SparseBitSet::intersection_inplace(self, other)
+    }
+
+    fn difference_inplace(&mut self, other: &Self) {
+        SparseBitSet::difference_inplace(self, other)
+    }
+
+    fn symmetric_difference_inplace(&mut self, other: &Self) {
+        SparseBitSet::symmetric_difference_inplace(self, other)
+    }
+
+    fn intersection_len(&self, other: &Self) -> usize {
+        SparseBitSet::intersection_len(self, other)
+    }
+
+    fn is_disjoint(&self, other: &Self) -> bool {
+        SparseBitSet::is_disjoint(self, other)
+    }
+}
+
+/**
+* Orders sets by their values in increasing order, the same way as `BitSet`
+*/
+impl Ord for SparseBitSet {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl PartialOrd for SparseBitSet {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/** Writes values as `{1, 4..=9}`, like `BitSet` */
+impl Display for SparseBitSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_values(self.iter(), f)
+    }
+}
+
+macro_rules! impl_set_op {
+    ($op:ident, $method:ident, $assign_op:ident, $assign_method:ident, $inplace:ident) => {
+        impl $assign_op<&SparseBitSet> for SparseBitSet {
+            fn $assign_method(&mut self, rhs: &SparseBitSet) {
+                self.$inplace(rhs);
+            }
+        }
+
+        impl $assign_op<SparseBitSet> for SparseBitSet {
+            fn $assign_method(&mut self, rhs: SparseBitSet) {
+                self.$inplace(&rhs);
+            }
+        }
+
+        impl $op<&SparseBitSet> for &SparseBitSet {
+            type Output = SparseBitSet;
+
+            fn $method(self, rhs: &SparseBitSet) -> SparseBitSet {
+                let mut result = self.clone();
+                result.$inplace(rhs);
+                result
+            }
+        }
+
+        impl $op<SparseBitSet> for &SparseBitSet {
+            type Output = SparseBitSet;
+
+            fn $method(self, rhs: SparseBitSet) -> SparseBitSet {
+                self.$method(&rhs)
+            }
+        }
+
+        impl $op<&SparseBitSet> for SparseBitSet {
+            type Output = SparseBitSet;
+
+            fn $method(mut self, rhs: &SparseBitSet) -> SparseBitSet {
+                self.$inplace(rhs);
+                self
+            }
+        }
+
+        impl $op<SparseBitSet> for SparseBitSet {
+            type Output = SparseBitSet;
+
+            fn $method(mut self, rhs: SparseBitSet) -> SparseBitSet {
+                self.$inplace(&rhs);
+                self
+            }
+        }
+    };
+}
+
+impl_set_op!(
+    BitAnd,
+    bitand,
+    BitAndAssign,
+    bitand_assign,
+    intersection_inplace
+);
+impl_set_op!(BitOr, bitor, BitOrAssign, bitor_assign, union_inplace);
+impl_set_op!(
+    BitXor,
+    bitxor,
+    BitXorAssign,
+    bitxor_assign,
+    symmetric_difference_inplace
+);
+impl_set_op!(Sub, sub, SubAssign, sub_assign, difference_inplace);
+
+impl FromIterator<u64> for SparseBitSet {
+    fn from_iter<I: IntoIterator<Item = u64>>(iter: I) -> Self {
+        let mut set = SparseBitSet::new();
+        for value in iter {
+            set.insert(value);
+        }
+        set
+    }
+}
+
+pub struct SparseBitSetIter<'a> {
+    chunks: std::collections::btree_map::Iter<'a, u64, Container>,
+    current: Option<(u64, ContainerIter<'a>)>,
+}
+
+impl Iterator for SparseBitSetIter<'_> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((high, it)) = self.current.as_mut() {
+                if let Some(low) = it.next() {
+                    return Some((*high << 16) | low as u64);
+                }
+            }
+            let (high, container) = self.chunks.next()?;
+            self.current = Some((*high, container.iter()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sparse_bitset::*;
+
+    #[test]
+    fn test_large_values() {
+        let mut v = SparseBitSet::new();
+        v.insert(1 << 40);
+        v.insert(u64::MAX);
+        v.insert(3);
+        v.insert(1 << 40);
+        assert_eq!(v.len(), 3);
+        assert!(v.contains(1 << 40));
+        assert!(!v.contains((1 << 40) + 1));
+        assert_eq!(v.iter().collect::<Vec<_>>(), vec![3, 1 << 40, u64::MAX]);
+
+        v.remove(1 << 40);
+        assert_eq!(v.len(), 2);
+        assert_eq!(v, [3, u64::MAX].into_iter().collect());
+    }
+
+    #[test]
+    fn test_dense_chunk_round_trip() {
+        let mut v: SparseBitSet = (0..10_000).collect();
+        assert_eq!(v.len(), 10_000);
+        assert!(matches!(v.chunks[&0], Container::Bitmap(..)));
+        assert_eq!(
+            v.iter().collect::<Vec<_>>(),
+            (0..10_000).collect::<Vec<_>>()
+        );
+
+        for x in 0..9_000 {
+            v.remove(x);
+        }
+        assert!(matches!(v.chunks[&0], Container::Array(..)));
+        assert_eq!(v, (9_000..10_000).collect());
+    }
+
+    #[test]
+    fn test_set_ops() {
+        let a: SparseBitSet = (0..6000).chain([1 << 50, 1 << 51]).collect();
+        let b: SparseBitSet = (5000..7000).chain([1 << 50]).collect();
+
+        let mut i = a.clone();
+        i.intersection_inplace(&b);
+        assert_eq!(i, (5000..6000).chain([1 << 50]).collect());
+        assert_eq!(a.intersection_len(&b), 1001);
+
+        let mut u = a.clone();
+        u.union_inplace(&b);
+        assert_eq!(u, (0..7000).chain([1 << 50, 1 << 51]).collect());
+
+        let mut d = a.clone();
+        d.difference_inplace(&b);
+        assert_eq!(d, (0..5000).chain([1 << 51]).collect());
+
+        let mut x = a.clone();
+        x.symmetric_difference_inplace(&b);
+        assert_eq!(x, (0..5000).chain(6000..7000).chain([1 << 51]).collect());
+        assert_eq!(x.len(), 6001);
+
+        assert!(d.is_disjoint(&b));
+        assert!(i.is_subset(&a));
+    }
+
+    #[test]
+    fn test_mixed_containers() {
+        let small: SparseBitSet = [1, 5, 9, 70_000].into_iter().collect();
+        let other: SparseBitSet = [5, 6, 9].into_iter().collect();
+        let dense: SparseBitSet = (0..5000).collect();
+
+        let u = &small | &other;
+        assert!(matches!(u.chunks[&0], Container::Array(..)));
+        assert_eq!(u, [1, 5, 6, 9, 70_000].into_iter().collect());
+        assert_eq!(&small & &other, [5, 9].into_iter().collect());
+        assert_eq!(&small - &other, [1, 70_000].into_iter().collect());
+        assert_eq!(&small ^ &other, [1, 6, 70_000].into_iter().collect());
+
+        let i = &small & &dense;
+        assert!(matches!(i.chunks[&0], Container::Array(..)));
+        assert_eq!(i, [1, 5, 9].into_iter().collect());
+        assert_eq!(&dense & &small, i);
+        assert_eq!(&small - &dense, [70_000].into_iter().collect());
+        assert_eq!(
+            &dense - &small,
+            (0..5000).filter(|v| ![1, 5, 9].contains(v)).collect()
+        );
+        assert_eq!((&small | &dense).len(), 5001);
+        assert_eq!((&dense ^ &small).len(), 4998);
+
+        let mut a = small.clone();
+        a -= &other;
+        a |= other;
+        assert_eq!(a, [1, 5, 6, 9, 70_000].into_iter().collect());
+    }
+}
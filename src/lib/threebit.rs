@@ -0,0 +1,678 @@
+use std::collections::HashSet;
+use std::fmt::{self, Display};
+
+/** The three registers of the 3-bit computer */
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Registers {
+    pub a: u64,
+    pub b: u64,
+    pub c: u64,
+}
+
+impl Registers {
+    pub fn new(a: u64, b: u64, c: u64) -> Self {
+        Registers { a, b, c }
+    }
+}
+
+/** Why the VM could not execute an instruction */
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum VmError {
+    /** A combo operand of 7, which is reserved */
+    ReservedOperand { ip: usize },
+    /** A `jnz` beyond the end of the program, further than the halting position just past it */
+    BadJumpTarget { ip: usize, target: u8 },
+    /** A program value that does not fit in 3 bits */
+    InvalidValue { ip: usize, value: u8 },
+    /** More instructions were executed than the configured limit */
+    InstructionLimit { limit: usize },
+}
+
+impl Display for VmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VmError::ReservedOperand { ip } => write!(f, "reserved combo operand 7 at {}", ip),
+            VmError::BadJumpTarget { ip, target } => {
+                write!(
+                    f,
+                    "jump at {} to {}, past the end of the program",
+                    ip, target
+                )
+            }
+            VmError::InvalidValue { ip, value } => {
+                write!(f, "value {} at {} does not fit in 3 bits", value, ip)
+            }
+            VmError::InstructionLimit { limit } => {
+                write!(f, "did not halt within {} instructions", limit)
+            }
+        }
+    }
+}
+
+impl std::error::Error for VmError {}
+
+/** Where a call to `run` stopped */
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Status {
+    /** The instruction pointer moved past the end of the program */
+    Halted,
+    /** About to execute the instruction at this breakpoint */
+    Breakpoint(usize),
+}
+
+/** One executed instruction, as recorded by a tracing VM */
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TraceEntry {
+    pub ip: usize,
+    pub opcode: u8,
+    pub operand: u8,
+    /** The registers after the instruction ran */
+    pub registers: Registers,
+    pub output: Option<u8>,
+}
+
+/**
+* The 3-bit computer from day 17, as a VM that can be stepped, stopped at breakpoints
+* and traced.
+*/
+#[derive(Clone, Debug)]
+pub struct Vm {
+    program: Vec<u8>,
+    registers: Registers,
+    ip: usize,
+    output: Vec<u8>,
+    steps: usize,
+    instruction_limit: Option<usize>,
+    breakpoints: HashSet<usize>,
+    /** The breakpoint `run` last stopped at, which the next `run` steps over */
+    stopped_at: Option<usize>,
+    trace: Option<Vec<TraceEntry>>,
+}
+
+impl Vm {
+    pub fn new(program: Vec<u8>, registers: Registers) -> Self {
+        Vm {
+            program,
+            registers,
+            ip: 0,
+            output: Vec::new(),
+            steps: 0,
+            instruction_limit: None,
+            breakpoints: HashSet::new(),
+            stopped_at: None,
+            trace: None,
+        }
+    }
+
+    /** Fails with `InstructionLimit` instead of executing more than `limit` instructions */
+    pub fn with_instruction_limit(mut self, limit: usize) -> Self {
+        self.instruction_limit = Some(limit);
+        self
+    }
+
+    /** Records every executed instruction, see `trace` */
+    pub fn with_trace(mut self) -> Self {
+        self.trace = Some(Vec::new());
+        self
+    }
+
+    pub fn add_breakpoint(&mut self, ip: usize) {
+        self.breakpoints.insert(ip);
+    }
+
+    pub fn remove_breakpoint(&mut self, ip: usize) {
+        self.breakpoints.remove(&ip);
+    }
+
+    pub fn program(&self) -> &[u8] {
+        &self.program
+    }
+
+    pub fn registers(&self) -> Registers {
+        self.registers
+    }
+
+    pub fn ip(&self) -> usize {
+        self.ip
+    }
+
+    pub fn output(&self) -> &[u8] {
+        &self.output
+    }
+
+    /** Number of instructions executed so far */
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /** The executed instructions, empty unless built `with_trace` */
+    pub fn trace(&self) -> &[TraceEntry] {
+        self.trace.as_deref().unwrap_or_default()
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.ip + 1 >= self.program.len()
+    }
+
+    fn value_at(&self, ip: usize) -> Result<u8, VmError> {
+        match self.program[ip] {
+            value @ 0..=7 => Ok(value),
+            value => Err(VmError::InvalidValue { ip, value }),
+        }
+    }
+
+    fn combo(&self, operand: u8) -> Result<u64, VmError> {
+        match operand {
+            0..=3 => Ok(operand as u64),
+            4 => Ok(self.registers.a),
+            5 => Ok(self.registers.b),
+            6 => Ok(self.registers.c),
+            _ => Err(VmError::ReservedOperand { ip: self.ip }),
+        }
+    }
+
+    /**
+     * Executes one instruction, returning the value it output if any.
+     * Does nothing once the program has halted.
+     */
+    pub fn step(&mut self) -> Result<Option<u8>, VmError> {
+        if self.is_halted() {
+            return Ok(None);
+        }
+        if let Some(limit) = self.instruction_limit {
+            if self.steps >= limit {
+                return Err(VmError::InstructionLimit { limit });
+            }
+        }
+        self.stopped_at = None;
+        let ip = self.ip;
+        let opcode = self.value_at(ip)?;
+        let operand = self.value_at(ip + 1)?;
+        let mut next_ip = ip + 2;
+        let mut output = None;
+        match opcode {
+            0 => self.registers.a = shr(self.registers.a, self.combo(operand)?),
+            1 => self.registers.b ^= operand as u64,
+            2 => self.registers.b = self.combo(operand)? % 8,
+            // an odd target is allowed, and reads the following values as shifted pairs
+            3 if self.registers.a != 0 => {
+                if operand as usize > self.program.len() {
+                    return Err(VmError::BadJumpTarget {
+                        ip,
+                        target: operand,
+                    });
+                }
+                next_ip = operand as usize;
+            }
+            3 => (),
+            4 => self.registers.b ^= self.registers.c,
+            5 => output = Some((self.combo(operand)? % 8) as u8),
+            6 => self.registers.b = shr(self.registers.a, self.combo(operand)?),
+            _ => self.registers.c = shr(self.registers.a, self.combo(operand)?),
+        }
+
+        self.ip = next_ip;
+        self.steps += 1;
+        self.output.extend(output);
+        if let Some(trace) = self.trace.as_mut() {
+            trace.push(TraceEntry {
+                ip,
+                opcode,
+                operand,
+                registers: self.registers,
+                output,
+            });
+        }
+        Ok(output)
+    }
+
+    /**
+     * Runs until the program halts or reaches a breakpoint, including one at the instruction
+     * it starts on. Calling `run` again after stopping at a breakpoint resumes past it.
+     */
+    pub fn run(&mut self) -> Result<Status, VmError> {
+        let mut resuming = self.stopped_at.take() == Some(self.ip);
+        while !self.is_halted() {
+            if !resuming && self.breakpoints.contains(&self.ip) {
+                self.stopped_at = Some(self.ip);
+                return Ok(Status::Breakpoint(self.ip));
+            }
+            resuming = false;
+            self.step()?;
+        }
+        Ok(Status::Halted)
+    }
+
+    /** Runs `program` from `registers` until it halts, ignoring breakpoints */
+    pub fn run_to_end(program: Vec<u8>, registers: Registers) -> Result<Vec<u8>, VmError> {
+        let mut vm = Vm::new(program, registers);
+        while !vm.is_halted() {
+            vm.step()?;
+        }
+        Ok(vm.output)
+    }
+}
+
+/** `value / 2^shift`, which is 0 once the shift is 64 or more */
+fn shr(value: u64, shift: u64) -> u64 {
+    u32::try_from(shift)
+        .ok()
+        .and_then(|s| value.checked_shr(s))
+        .unwrap_or(0)
+}
+
+/** Parses the `Register A: ...` / `Program: ...` puzzle input */
+pub fn parse_input(input: &str) -> Option<(Registers, Vec<u8>)> {
+    let mut lines = input.lines().filter(|line| !line.trim().is_empty());
+    let mut register = || lines.next()?.split_once(": ")?.1.trim().parse().ok();
+    let registers = Registers::new(register()?, register()?, register()?);
+    let program = parse_program(lines.next()?.split_once(": ")?.1)?;
+    Some((registers, program))
+}
+
+/** Parses a comma-separated program such as `0,1,5,4,3,0` */
+pub fn parse_program(s: &str) -> Option<Vec<u8>> {
+    s.trim().split(',').map(|v| v.trim().parse().ok()).collect()
+}
+
+/** The eight instructions, in opcode order */
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Opcode {
+    Adv,
+    Bxl,
+    Bst,
+    Jnz,
+    Bxc,
+    Out,
+    Bdv,
+    Cdv,
+}
+
+impl Opcode {
+    pub const ALL: [Opcode; 8] = [
+        Opcode::Adv,
+        Opcode::Bxl,
+        Opcode::Bst,
+        Opcode::Jnz,
+        Opcode::Bxc,
+        Opcode::Out,
+        Opcode::Bdv,
+        Opcode::Cdv,
+    ];
+
+    pub fn from_u8(value: u8) -> Option<Opcode> {
+        Opcode::ALL.get(value as usize).copied()
+    }
+
+    pub fn to_u8(self) -> u8 {
+        self as u8
+    }
+
+    pub fn mnemonic(self) -> &'static str {
+        match self {
+            Opcode::Adv => "adv",
+            Opcode::Bxl => "bxl",
+            Opcode::Bst => "bst",
+            Opcode::Jnz => "jnz",
+            Opcode::Bxc => "bxc",
+            Opcode::Out => "out",
+            Opcode::Bdv => "bdv",
+            Opcode::Cdv => "cdv",
+        }
+    }
+
+    pub fn from_mnemonic(s: &str) -> Option<Opcode> {
+        Opcode::ALL.into_iter().find(|op| op.mnemonic() == s)
+    }
+
+    /** Whether the operand is a combo operand rather than a literal */
+    pub fn takes_combo(self) -> bool {
+        matches!(
+            self,
+            Opcode::Adv | Opcode::Bst | Opcode::Out | Opcode::Bdv | Opcode::Cdv
+        )
+    }
+}
+
+/**
+* Turns a program into one `mnemonic operand` line per instruction, with combo operands
+* 4 to 6 written as the register they read, e.g. `bst A`.
+* A trailing unpaired value is ignored, as the VM would halt before it.
+*/
+pub fn disassemble(program: &[u8]) -> Result<String, VmError> {
+    let mut lines = Vec::new();
+    for (idx, pair) in program.chunks_exact(2).enumerate() {
+        let ip = 2 * idx;
+        let opcode =
+            Opcode::from_u8(pair[0]).ok_or(VmError::InvalidValue { ip, value: pair[0] })?;
+        let operand = match (pair[1], opcode.takes_combo()) {
+            (value @ 8.., _) => return Err(VmError::InvalidValue { ip: ip + 1, value }),
+            (7, true) => return Err(VmError::ReservedOperand { ip }),
+            (4, true) => "A".to_string(),
+            (5, true) => "B".to_string(),
+            (6, true) => "C".to_string(),
+            (value, _) => value.to_string(),
+        };
+        lines.push(format!("{} {}", opcode.mnemonic(), operand));
+    }
+    Ok(lines.join("\n"))
+}
+
+/** Returned when a line of assembly cannot be understood */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AssembleError {
+    /** 1-based line number */
+    pub line: usize,
+    pub message: String,
+}
+
+impl Display for AssembleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for AssembleError {}
+
+/**
+* The inverse of `disassemble`: one instruction per line, with `#` starting a comment.
+* Combo operands may be written as a register name or as their number, except the
+* reserved 7.
+*/
+pub fn assemble(source: &str) -> Result<Vec<u8>, AssembleError> {
+    let mut program = Vec::new();
+    for (idx, line) in source.lines().enumerate() {
+        let error = |message: String| AssembleError {
+            line: idx + 1,
+            message,
+        };
+        let code = line.split('#').next().unwrap_or_default().trim();
+        if code.is_empty() {
+            continue;
+        }
+        let mut words = code.split_whitespace();
+        let mnemonic = words.next().unwrap_or_default();
+        let opcode = Opcode::from_mnemonic(&mnemonic.to_lowercase())
+            .ok_or_else(|| error(format!("unknown instruction `{}`", mnemonic)))?;
+        let operand = words
+            .next()
+            .ok_or_else(|| error(format!("`{}` needs an operand", mnemonic)))?;
+        if let Some(extra) = words.next() {
+            return Err(error(format!("unexpected `{}`", extra)));
+        }
+        let value = match (operand, opcode.takes_combo()) {
+            ("A" | "a", true) => 4,
+            ("B" | "b", true) => 5,
+            ("C" | "c", true) => 6,
+            (literal, combo) => match literal.parse::<u8>() {
+                Ok(v) if v <= 6 || (!combo && v == 7) => v,
+                _ => return Err(error(format!("invalid operand `{}`", literal))),
+            },
+        };
+        program.extend([opcode.to_u8(), value]);
+    }
+    Ok(program)
+}
+
+/** Why `find_quine` could not search a program */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum QuineError {
+    /** The program is not a loop that shifts A by 3 and outputs once per iteration */
+    UnsupportedShape(String),
+    /** The program failed while running a candidate */
+    Vm(VmError),
+}
+
+impl Display for QuineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuineError::UnsupportedShape(reason) => write!(f, "unsupported program: {}", reason),
+            QuineError::Vm(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for QuineError {}
+
+impl From<VmError> for QuineError {
+    fn from(value: VmError) -> Self {
+        QuineError::Vm(value)
+    }
+}
+
+/**
+* Checks that `program` is a single loop ending in `jnz 0`, with exactly one `adv 3` and
+* one `out` in its body. Each iteration then outputs one value and drops the lowest
+* 3 bits of A, so the last outputs only depend on the highest bits of A.
+*/
+fn check_quine_shape(program: &[u8]) -> Result<(), QuineError> {
+    let unsupported = |reason: &str| Err(QuineError::UnsupportedShape(reason.to_string()));
+    if !program.len().is_multiple_of(2) {
+        return unsupported("it has an odd number of values");
+    }
+    if program.len() * 3 > 64 {
+        return unsupported("its quine would need more than 64 bits of A");
+    }
+    let instructions: Vec<(u8, u8)> = program.chunks_exact(2).map(|p| (p[0], p[1])).collect();
+    let count = |op: Opcode| {
+        instructions
+            .iter()
+            .filter(|(o, _)| *o == op.to_u8())
+            .count()
+    };
+
+    if instructions.last() != Some(&(Opcode::Jnz.to_u8(), 0)) {
+        return unsupported("it does not end with `jnz 0`");
+    }
+    if count(Opcode::Jnz) != 1 {
+        return unsupported("it has more than one jump");
+    }
+    if count(Opcode::Adv) != 1 || !instructions.contains(&(Opcode::Adv.to_u8(), 3)) {
+        return unsupported("it does not shift A by exactly 3 bits per iteration");
+    }
+    if count(Opcode::Out) != 1 {
+        return unsupported("it does not output exactly once per iteration");
+    }
+    Ok(())
+}
+
+/**
+* The smallest value of register A that makes `program` output a copy of itself, with B
+* and C taken from `registers`. Builds A three bits at a time from the highest bits down,
+* running each candidate on the VM and keeping it only if it produces the matching tail
+* of the program. Assumes every iteration recomputes B and C from A, as the puzzle
+* inputs do; any answer returned has been checked against the full program anyway.
+*/
+pub fn find_quine(program: &[u8], registers: Registers) -> Result<Option<u64>, QuineError> {
+    check_quine_shape(program)?;
+    quine_search(program, registers, 0, 0)
+}
+
+fn quine_search(
+    program: &[u8],
+    registers: Registers,
+    prefix: u64,
+    matched: usize,
+) -> Result<Option<u64>, QuineError> {
+    if matched == program.len() {
+        return Ok(Some(prefix));
+    }
+    let tail = &program[program.len() - matched - 1..];
+    // one loop iteration per output, plus slack for candidates that go wrong
+    let limit = program.len() * (tail.len() + 1);
+    for bits in 0..8 {
+        let a = (prefix << 3) | bits;
+        let mut vm =
+            Vm::new(program.to_vec(), Registers { a, ..registers }).with_instruction_limit(limit);
+        while !vm.is_halted() && vm.output().len() <= tail.len() {
+            vm.step()?;
+        }
+        if vm.output() == tail {
+            if let Some(found) = quine_search(program, registers, a, matched + 1)? {
+                return Ok(Some(found));
+            }
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::threebit::*;
+
+    #[test]
+    fn test_examples() {
+        let program = vec![0, 1, 5, 4, 3, 0];
+        assert_eq!(
+            Vm::run_to_end(program, Registers::new(2024, 0, 0)),
+            Ok(vec![4, 2, 5, 6, 7, 7, 7, 7, 3, 1, 0])
+        );
+        let mut vm = Vm::new(vec![1, 7], Registers::new(0, 29, 0));
+        vm.run().unwrap();
+        assert_eq!(vm.registers().b, 26);
+        let mut vm = Vm::new(vec![4, 0], Registers::new(0, 2024, 43690));
+        vm.run().unwrap();
+        assert_eq!(vm.registers().b, 44354);
+    }
+
+    #[test]
+    fn test_parse_and_run() {
+        let input = "Register A: 729\nRegister B: 0\nRegister C: 0\n\nProgram: 0,1,5,4,3,0\n";
+        let (registers, program) = parse_input(input).unwrap();
+        assert_eq!(registers, Registers::new(729, 0, 0));
+        assert_eq!(
+            Vm::run_to_end(program, registers),
+            Ok(vec![4, 6, 3, 5, 6, 3, 5, 2, 1, 0])
+        );
+    }
+
+    #[test]
+    fn test_breakpoints_and_trace() {
+        let mut vm = Vm::new(vec![0, 1, 5, 4, 3, 0], Registers::new(8, 0, 0)).with_trace();
+        vm.add_breakpoint(2);
+        assert_eq!(vm.run(), Ok(Status::Breakpoint(2)));
+        assert_eq!(vm.registers().a, 4);
+        assert_eq!(vm.run(), Ok(Status::Breakpoint(2)));
+        assert_eq!(vm.output(), &[4]);
+        vm.remove_breakpoint(2);
+        assert_eq!(vm.run(), Ok(Status::Halted));
+        assert_eq!(vm.output(), &[4, 2, 1, 0]);
+        assert_eq!(vm.steps(), 12);
+        assert_eq!(vm.trace().len(), 12);
+        assert_eq!(vm.trace()[1].output, Some(4));
+        assert_eq!(vm.trace()[0].registers.a, 4);
+
+        // a breakpoint on the first instruction stops the first run before anything executes
+        let mut vm = Vm::new(vec![0, 1, 5, 4, 3, 0], Registers::new(8, 0, 0));
+        vm.add_breakpoint(0);
+        assert_eq!(vm.run(), Ok(Status::Breakpoint(0)));
+        assert_eq!(vm.steps(), 0);
+        assert_eq!(vm.run(), Ok(Status::Breakpoint(0)));
+        assert_eq!(vm.output(), &[4]);
+        assert_eq!(vm.steps(), 3);
+    }
+
+    #[test]
+    fn test_disassemble() {
+        let program = parse_program("2,4,1,1,7,5,1,5,4,3,0,3,5,5,3,0").unwrap();
+        let text = disassemble(&program).unwrap();
+        assert_eq!(
+            text,
+            "bst A\nbxl 1\ncdv B\nbxl 5\nbxc 3\nadv 3\nout B\njnz 0"
+        );
+        assert_eq!(assemble(&text), Ok(program));
+        assert_eq!(
+            disassemble(&[5, 7]),
+            Err(VmError::ReservedOperand { ip: 0 })
+        );
+        assert_eq!(disassemble(&[1, 7]).unwrap(), "bxl 7");
+    }
+
+    #[test]
+    fn test_assemble() {
+        let source = "# print A in octal\nloop: \n";
+        assert_eq!(assemble(source).unwrap_err().line, 2);
+        let source = "\n# print A in octal, lowest digit first\nOUT a\nadv 3 # next digit\njnz 0\n";
+        let program = assemble(source).unwrap();
+        assert_eq!(program, vec![5, 4, 0, 3, 3, 0]);
+        assert_eq!(
+            Vm::run_to_end(program, Registers::new(0o1234, 0, 0)),
+            Ok(vec![4, 3, 2, 1])
+        );
+        assert_eq!(assemble("adv 4").unwrap(), vec![0, 4]);
+        assert_eq!(
+            assemble("adv 7").unwrap_err().message,
+            "invalid operand `7`"
+        );
+        assert_eq!(
+            assemble("bxl A").unwrap_err().message,
+            "invalid operand `A`"
+        );
+        assert!(assemble("jnz").is_err());
+        assert!(assemble("nop 1").is_err());
+    }
+
+    #[test]
+    fn test_find_quine() {
+        let example = parse_program("0,3,5,4,3,0").unwrap();
+        assert_eq!(find_quine(&example, Registers::default()), Ok(Some(117440)));
+
+        let program = parse_program("2,4,1,1,7,5,1,5,4,3,0,3,5,5,3,0").unwrap();
+        let a = find_quine(&program, Registers::default()).unwrap().unwrap();
+        assert_eq!(
+            Vm::run_to_end(program.clone(), Registers::new(a, 0, 0)),
+            Ok(program)
+        );
+
+        let unsupported = |source: &str| {
+            let program = assemble(source).unwrap();
+            matches!(
+                find_quine(&program, Registers::default()),
+                Err(QuineError::UnsupportedShape(_))
+            )
+        };
+        assert!(unsupported("out A\nadv 3"));
+        assert!(unsupported("adv 2\nout A\njnz 0"));
+        assert!(unsupported("adv 3\nout A\nout B\njnz 0"));
+        assert!(unsupported("adv 3\nout A\njnz 2\njnz 0"));
+    }
+
+    #[test]
+    fn test_jumps() {
+        // jnz 1 runs `out 3` from the values at 1 and 2, then halts at 3
+        assert_eq!(
+            Vm::run_to_end(vec![0, 5, 3, 1], Registers::new(1, 0, 0)),
+            Ok(vec![3])
+        );
+        // jumping to just past the end halts
+        assert_eq!(
+            Vm::run_to_end(vec![5, 4, 3, 4], Registers::new(1, 0, 0)),
+            Ok(vec![1])
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let looping = Vm::new(vec![3, 0], Registers::new(1, 0, 0));
+        assert_eq!(
+            looping.with_instruction_limit(100).run(),
+            Err(VmError::InstructionLimit { limit: 100 })
+        );
+        assert_eq!(
+            Vm::run_to_end(vec![5, 7], Registers::default()),
+            Err(VmError::ReservedOperand { ip: 0 })
+        );
+        assert_eq!(
+            Vm::run_to_end(vec![3, 7], Registers::new(1, 0, 0)),
+            Err(VmError::BadJumpTarget { ip: 0, target: 7 })
+        );
+        assert_eq!(
+            Vm::run_to_end(vec![9, 0], Registers::default()),
+            Err(VmError::InvalidValue { ip: 0, value: 9 })
+        );
+        assert_eq!(
+            Vm::run_to_end(vec![0, 4], Registers::new(u64::MAX, 0, 0)),
+            Ok(vec![])
+        );
+    }
+}
@@ -1,144 +1,446 @@
-use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Rem, Sub, SubAssign};
+use std::ops::{Add, Mul, Sub};
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+use crate::{Dir, GridIndex, Loc, Point};
+
+/**
+* A 2D vector in screen coordinates: x grows to the east (the column) and y grows to the
+* south (the row). Converting to or from the (row, col) `Loc` and `Point` always goes
+* through the explicit conversions below, never through a plain tuple.
+*/
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Vec2<T> {
     pub x: T,
     pub y: T,
 }
 
-impl<T> Add<Vec2<T>> for Vec2<T>
-where
-    T: Add<Output = T>,
-{
-    type Output = Vec2<T>;
-
-    fn add(self, rhs: Vec2<T>) -> Self::Output {
-        Vec2 {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y,
-        }
+impl<T> Vec2<T> {
+    pub fn new(x: T, y: T) -> Self {
+        Vec2 { x, y }
     }
 }
 
-impl<T> AddAssign<Vec2<T>> for Vec2<T>
+impl<T> Vec2<T>
 where
-    T: AddAssign<T>,
+    T: Into<f64> + Copy,
 {
-    fn add_assign(&mut self, rhs: Vec2<T>) {
-        self.x += rhs.x;
-        self.y += rhs.y;
+    pub fn magnitude(&self) -> f64 {
+        (self.x.into() * self.x.into() + self.y.into() * self.y.into()).sqrt()
     }
 }
 
-impl<T> Mul<T> for Vec2<T>
-where
-    T: Mul<Output = T> + Copy,
-{
-    type Output = Vec2<T>;
-    fn mul(self, rhs: T) -> Self::Output {
-        Vec2 {
-            x: self.x * rhs,
-            y: self.y * rhs,
+/**
+* The component-wise operators shared by `Vec2`, `Vec3` and `VecN`: `+`, `-` and their
+* assigning forms between vectors, `*`, `/` and `%` by a scalar, negation and `Sum`.
+* For a struct with named fields, list the fields, e.g. `impl_vec_ops!(Vec2 { x, y })`.
+* Any other vector type names its generics, e.g. `impl_vec_ops!(VecN<T, const N: usize>)`,
+* and provides `zip_with`, `map`, `zip_assign`, `map_assign` and `fold` itself.
+*/
+macro_rules! impl_vec_ops {
+    ($vec:ident { $($f:ident),+ }) => {
+        impl<T> $vec<T> {
+            fn zip_with(self, other: Self, mut op: impl FnMut(T, T) -> T) -> Self {
+                $vec { $($f: op(self.$f, other.$f)),+ }
+            }
+
+            fn map(self, mut op: impl FnMut(T) -> T) -> Self {
+                $vec { $($f: op(self.$f)),+ }
+            }
+
+            fn zip_assign(&mut self, other: Self, mut op: impl FnMut(&mut T, T)) {
+                $(op(&mut self.$f, other.$f);)+
+            }
+
+            fn map_assign(&mut self, mut op: impl FnMut(&mut T)) {
+                $(op(&mut self.$f);)+
+            }
+
+            fn fold<A>(self, init: A, mut op: impl FnMut(A, T) -> A) -> A {
+                let acc = init;
+                $(let acc = op(acc, self.$f);)+
+                acc
+            }
         }
-    }
-}
 
-impl<T> MulAssign<T> for Vec2<T>
-where
-    T: MulAssign<T> + Copy,
-{
-    fn mul_assign(&mut self, rhs: T) {
-        self.x *= rhs;
-        self.y *= rhs;
-    }
+        $crate::lib::vec2::impl_vec_ops!($vec<T>);
+    };
+    ($vec:ident<T $(, const $n:ident: usize)?>) => {
+        impl<T $(, const $n: usize)?> std::ops::Add for $vec<T $(, $n)?>
+        where
+            T: std::ops::Add<Output = T>,
+        {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self::Output {
+                self.zip_with(rhs, |a, b| a + b)
+            }
+        }
+
+        impl<T $(, const $n: usize)?> std::ops::AddAssign for $vec<T $(, $n)?>
+        where
+            T: std::ops::AddAssign,
+        {
+            fn add_assign(&mut self, rhs: Self) {
+                self.zip_assign(rhs, |a, b| *a += b);
+            }
+        }
+
+        impl<T $(, const $n: usize)?> std::ops::Sub for $vec<T $(, $n)?>
+        where
+            T: std::ops::Sub<Output = T>,
+        {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self::Output {
+                self.zip_with(rhs, |a, b| a - b)
+            }
+        }
+
+        impl<T $(, const $n: usize)?> std::ops::SubAssign for $vec<T $(, $n)?>
+        where
+            T: std::ops::SubAssign,
+        {
+            fn sub_assign(&mut self, rhs: Self) {
+                self.zip_assign(rhs, |a, b| *a -= b);
+            }
+        }
+
+        impl<T $(, const $n: usize)?> std::ops::Mul<T> for $vec<T $(, $n)?>
+        where
+            T: std::ops::Mul<Output = T> + Copy,
+        {
+            type Output = Self;
+
+            fn mul(self, rhs: T) -> Self::Output {
+                self.map(|a| a * rhs)
+            }
+        }
+
+        impl<T $(, const $n: usize)?> std::ops::MulAssign<T> for $vec<T $(, $n)?>
+        where
+            T: std::ops::MulAssign + Copy,
+        {
+            fn mul_assign(&mut self, rhs: T) {
+                self.map_assign(|a| *a *= rhs);
+            }
+        }
+
+        impl<T $(, const $n: usize)?> std::ops::Div<T> for $vec<T $(, $n)?>
+        where
+            T: std::ops::Div<Output = T> + Copy,
+        {
+            type Output = Self;
+
+            fn div(self, rhs: T) -> Self::Output {
+                self.map(|a| a / rhs)
+            }
+        }
+
+        impl<T $(, const $n: usize)?> std::ops::DivAssign<T> for $vec<T $(, $n)?>
+        where
+            T: std::ops::DivAssign + Copy,
+        {
+            fn div_assign(&mut self, rhs: T) {
+                self.map_assign(|a| *a /= rhs);
+            }
+        }
+
+        impl<T $(, const $n: usize)?> std::ops::Rem<T> for $vec<T $(, $n)?>
+        where
+            T: std::ops::Rem<Output = T> + Copy,
+        {
+            type Output = Self;
+
+            fn rem(self, rhs: T) -> Self::Output {
+                self.map(|a| a % rhs)
+            }
+        }
+
+        impl<T $(, const $n: usize)?> std::ops::Neg for $vec<T $(, $n)?>
+        where
+            T: std::ops::Neg<Output = T>,
+        {
+            type Output = Self;
+
+            fn neg(self) -> Self::Output {
+                self.map(|a| -a)
+            }
+        }
+
+        impl<T $(, const $n: usize)?> std::iter::Sum for $vec<T $(, $n)?>
+        where
+            T: std::ops::Add<Output = T> + Default,
+        {
+            fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+                iter.fold(Self::default(), |acc, v| acc + v)
+            }
+        }
+    };
 }
 
-impl<T> Sub<Vec2<T>> for Vec2<T>
-where
-    T: Sub<Output = T>,
-{
-    type Output = Vec2<T>;
+pub(crate) use impl_vec_ops;
+
+/**
+* The helpers shared by `Vec2`, `Vec3` and `VecN` for one signed integer component type,
+* e.g. `impl_vec_signed!(Vec2<i64>)` or `impl_vec_signed!(VecN<i64, const N: usize>)`.
+* Builds on the helpers that `impl_vec_ops!` needs.
+*/
+macro_rules! impl_vec_signed {
+    ($vec:ident<$t:ty $(, const $n:ident: usize)?>) => {
+        impl<$(const $n: usize)?> $vec<$t $(, $n)?> {
+            pub fn abs(&self) -> Self {
+                self.map(|v| v.abs())
+            }
+
+            pub fn signum(&self) -> Self {
+                self.map(|v| v.signum())
+            }
+
+            /** Wraps each component into `0..modulus`, e.g. onto a torus */
+            pub fn rem_euclid(&self, modulus: Self) -> Self {
+                self.zip_with(modulus, |v, m| v.rem_euclid(m))
+            }
 
-    fn sub(self, rhs: Vec2<T>) -> Self::Output {
-        Vec2 {
-            x: self.x - rhs.x,
-            y: self.y - rhs.y,
+            pub fn manhattan(&self) -> $t {
+                self.fold(0, |sum, v| sum + v.abs())
+            }
+
+            pub fn chebyshev(&self) -> $t {
+                self.fold(0, |max, v| max.max(v.abs()))
+            }
+
+            /**
+             * The smallest integer step in the same direction, by dividing out the gcd
+             * of the components. The zero vector is returned unchanged.
+             */
+            pub fn normalize(&self) -> Self {
+                // divide the magnitudes, since |MIN| does not fit in the signed type
+                let divide = |v: $t, g: u128| {
+                    let q = (v.unsigned_abs() as u128 / g) as $t;
+                    if v < 0 { -q } else { q }
+                };
+                match self.fold(0, |g, v| $crate::math::gcd(g, v.unsigned_abs() as u128)) {
+                    0 | 1 => *self,
+                    g => self.map(|v| divide(v, g)),
+                }
+            }
         }
-    }
+    };
 }
 
-impl<T> SubAssign<Vec2<T>> for Vec2<T>
+pub(crate) use impl_vec_signed;
+
+impl_vec_ops!(Vec2 { x, y });
+
+impl<T> Vec2<T>
 where
-    T: SubAssign<T>,
+    T: Mul<Output = T> + Copy,
 {
-    fn sub_assign(&mut self, rhs: Vec2<T>) {
-        self.x -= rhs.x;
-        self.y -= rhs.y;
+    /** Multiplies component by component */
+    pub fn component_mul(&self, other: Vec2<T>) -> Vec2<T> {
+        Vec2::new(self.x * other.x, self.y * other.y)
     }
 }
 
-impl<A, B, T> From<(A, B)> for Vec2<T>
+impl<T> Vec2<T>
 where
-    A: Into<T>,
-    B: Into<T>,
+    T: Mul<Output = T> + Add<Output = T> + Copy,
 {
-    fn from(value: (A, B)) -> Self {
-        Self {
-            x: value.0.into(),
-            y: value.1.into(),
-        }
+    pub fn dot(&self, other: Vec2<T>) -> T {
+        self.x * other.x + self.y * other.y
     }
 }
 
-impl<T> Div<T> for Vec2<T>
+impl<T> Vec2<T>
 where
-    T: Div<Output = T> + Copy,
+    T: Mul<Output = T> + Sub<Output = T> + Copy,
 {
-    type Output = Vec2<T>;
-
-    fn div(self, rhs: T) -> Self::Output {
-        Vec2 {
-            x: self.x / rhs,
-            y: self.y / rhs,
-        }
+    /** z component of the 3D cross product, positive if `other` is counterclockwise of `self` */
+    pub fn cross(&self, other: Vec2<T>) -> T {
+        self.x * other.y - self.y * other.x
     }
 }
 
-impl<T> DivAssign<T> for Vec2<T>
-where
-    T: DivAssign<T> + Copy,
-{
-    fn div_assign(&mut self, rhs: T) {
-        self.x /= rhs;
-        self.y /= rhs;
-    }
+macro_rules! impl_signed {
+    ($($t:ty),*) => {
+        $(
+            impl_vec_signed!(Vec2<$t>);
+
+            impl Vec2<$t> {
+                /**
+                 * Vector for a grid location, with x the column and y the row, if both fit
+                 * in the component type
+                 */
+                pub fn from_loc(loc: Loc) -> Option<Self> {
+                    Some(Vec2::new(<$t>::try_from(loc.1).ok()?, <$t>::try_from(loc.0).ok()?))
+                }
+
+                /** The grid location of this vector, if neither component is negative */
+                pub fn to_loc(&self) -> Option<Loc> {
+                    Some(Loc(usize::try_from(self.y).ok()?, usize::try_from(self.x).ok()?))
+                }
+            }
+
+            /**
+             * Indexes a `Grid` with x as the column and y as the row.
+             * Components that do not fit in a `Point` are never in bounds.
+             */
+            impl GridIndex for Vec2<$t> {
+                fn to_point(&self) -> Option<Point> {
+                    Point::try_from(*self).ok()
+                }
+            }
+
+            /** The unit step in a direction, with x pointing east and y pointing south */
+            impl From<Dir> for Vec2<$t> {
+                fn from(value: Dir) -> Self {
+                    let (dr, dc) = value.delta();
+                    Vec2::new(dc as $t, dr as $t)
+                }
+            }
+
+            /** Fails unless the vector is one of the eight unit steps */
+            impl TryFrom<Vec2<$t>> for Dir {
+                type Error = Vec2<$t>;
+
+                fn try_from(value: Vec2<$t>) -> Result<Self, Self::Error> {
+                    Dir::ALL
+                        .into_iter()
+                        .find(|dir| Vec2::<$t>::from(*dir) == value)
+                        .ok_or(value)
+                }
+            }
+        )*
+    };
 }
 
-impl<T> Vec2<T> {
-    pub fn new(x: T, y: T) -> Self {
-        Vec2 { x, y }
-    }
+impl_signed!(i8, i16, i32, i64, i128, isize);
+
+/** Screen (x, y) to matrix (row, col) for components that always fit in a `Point` */
+macro_rules! impl_into_point {
+    ($($t:ty),*) => {
+        $(
+            /** Screen (x, y) to matrix (row, col), i.e. row = y and col = x */
+            impl From<Vec2<$t>> for Point {
+                fn from(value: Vec2<$t>) -> Self {
+                    Point::new(value.y.into(), value.x.into())
+                }
+            }
+        )*
+    };
 }
 
-impl<T> Vec2<T>
-where
-    T: Into<f64> + Copy,
-{
-    pub fn magnitude(&self) -> f64 {
-        (self.x.into() * self.x.into() + self.y.into() * self.y.into()).sqrt()
-    }
+/** Screen (x, y) to matrix (row, col) for components that may not fit in a `Point` */
+macro_rules! impl_try_into_point {
+    ($($t:ty),*) => {
+        $(
+            /** Fails if either component does not fit in an i64 */
+            impl TryFrom<Vec2<$t>> for Point {
+                type Error = Vec2<$t>;
+
+                fn try_from(value: Vec2<$t>) -> Result<Self, Self::Error> {
+                    match (i64::try_from(value.y), i64::try_from(value.x)) {
+                        (Ok(row), Ok(col)) => Ok(Point::new(row, col)),
+                        _ => Err(value),
+                    }
+                }
+            }
+        )*
+    };
 }
 
-impl<T> Rem<T> for Vec2<T>
-where
-    T: Rem<T, Output = T> + Copy,
-{
-    type Output = Self;
+/** Matrix (row, col) to screen (x, y) for types that can hold any i64 */
+macro_rules! impl_from_point {
+    ($($t:ty),*) => {
+        $(
+            /** Matrix (row, col) to screen (x, y), i.e. x = col and y = row */
+            impl From<Point> for Vec2<$t> {
+                fn from(value: Point) -> Self {
+                    Vec2::new(value.col.into(), value.row.into())
+                }
+            }
+        )*
+    };
+}
 
-    fn rem(self, rhs: T) -> Self::Output {
-        Self {
-            x: self.x % rhs,
-            y: self.y % rhs,
-        }
+/** Matrix (row, col) to screen (x, y) for types narrower than i64 */
+macro_rules! impl_try_from_point {
+    ($($t:ty),*) => {
+        $(
+            /** Fails if either component does not fit in the narrower type */
+            impl TryFrom<Point> for Vec2<$t> {
+                type Error = Point;
+
+                fn try_from(value: Point) -> Result<Self, Self::Error> {
+                    match (<$t>::try_from(value.col), <$t>::try_from(value.row)) {
+                        (Ok(x), Ok(y)) => Ok(Vec2::new(x, y)),
+                        _ => Err(value),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_into_point!(i8, i16, i32, i64);
+impl_try_into_point!(i128, isize);
+impl_from_point!(i64, i128);
+impl_try_from_point!(i8, i16, i32, isize);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_products_and_norms() {
+        let a = Vec2::new(3i32, -4);
+        let b = Vec2::new(2, 5);
+        assert_eq!(a.dot(b), -14);
+        assert_eq!(a.cross(b), 23);
+        assert_eq!(a.component_mul(b), Vec2::new(6, -20));
+        assert_eq!(a.manhattan(), 7);
+        assert_eq!(a.chebyshev(), 4);
+        assert_eq!(-a, Vec2::new(-3, 4));
+        assert_eq!(
+            vec![a, b, a].into_iter().sum::<Vec2<i32>>(),
+            Vec2::new(8, -3)
+        );
+    }
+
+    #[test]
+    fn test_integer_ops() {
+        let v = Vec2::new(-6i64, 9);
+        assert_eq!(v.abs(), Vec2::new(6, 9));
+        assert_eq!(v.signum(), Vec2::new(-1, 1));
+        assert_eq!(v.rem_euclid(Vec2::new(4, 5)), Vec2::new(2, 4));
+        assert_eq!(v.normalize(), Vec2::new(-2, 3));
+        assert_eq!(Vec2::new(0i64, 0).normalize(), Vec2::new(0, 0));
+        assert_eq!(Vec2::new(0i64, -7).normalize(), Vec2::new(0, -1));
+        assert_eq!(Vec2::new(i64::MIN, 0).normalize(), Vec2::new(-1, 0));
+        assert_eq!(Vec2::new(i8::MIN, i8::MIN).normalize(), Vec2::new(-1, -1));
+        assert_eq!(Vec2::new(i8::MIN, 64).normalize(), Vec2::new(-2, 1));
+        assert_eq!(Vec2::new(i8::MIN, 1).normalize(), Vec2::new(i8::MIN, 1));
+    }
+
+    #[test]
+    fn test_conversions() {
+        assert_eq!(Vec2::<i32>::from(Dir::NE), Vec2::new(1, -1));
+        assert_eq!(Dir::try_from(Vec2::new(-1i64, 0)), Ok(Dir::W));
+        assert!(Dir::try_from(Vec2::new(2i64, 0)).is_err());
+        assert_eq!(Vec2::<i64>::from_loc(Loc(2, 5)), Some(Vec2::new(5, 2)));
+        assert_eq!(Vec2::<i8>::from_loc(Loc(200, 3)), None);
+        assert_eq!(Vec2::<i8>::from_loc(Loc(3, 300)), None);
+        assert_eq!(Vec2::<i8>::from_loc(Loc(127, 0)), Some(Vec2::new(0, 127)));
+        assert_eq!(Vec2::new(5i64, 2).to_loc(), Some(Loc(2, 5)));
+        assert_eq!(Vec2::new(-1i64, 2).to_loc(), None);
+        assert_eq!(Point::from(Vec2::new(5i32, 2)), Point::new(2, 5));
+        assert_eq!(Vec2::<i64>::from(Point::new(2, 5)), Vec2::new(5, 2));
+        assert_eq!(Vec2::<i32>::try_from(Point::new(2, 5)), Ok(Vec2::new(5, 2)));
+        assert!(Vec2::<i8>::try_from(Point::new(300, 0)).is_err());
+        assert!(Point::try_from(Vec2::new(1i128 << 64, 0)).is_err());
+
+        let grid = crate::Grid(vec![vec!['a', 'b', 'c'], vec!['d', 'e', 'f']]);
+        assert_eq!(grid[Vec2::new(2i64, 1)], 'f');
+        assert_eq!(grid.at(&Vec2::new(1i32, 2)), None);
+        assert_eq!(grid.at(&Vec2::new(1i128 << 64, 0)), None);
     }
 }
@@ -0,0 +1,128 @@
+use std::ops::{Add, Mul, Sub};
+
+use super::vec2::{impl_vec_ops, impl_vec_signed};
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Vec3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Vec3<T> {
+    pub fn new(x: T, y: T, z: T) -> Self {
+        Vec3 { x, y, z }
+    }
+}
+
+impl_vec_ops!(Vec3 { x, y, z });
+
+impl<T> Vec3<T>
+where
+    T: Mul<Output = T> + Add<Output = T> + Sub<Output = T> + Copy,
+{
+    /** Multiplies component by component */
+    pub fn component_mul(&self, other: Vec3<T>) -> Vec3<T> {
+        Vec3::new(self.x * other.x, self.y * other.y, self.z * other.z)
+    }
+
+    pub fn dot(&self, other: Vec3<T>) -> T {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn cross(&self, other: Vec3<T>) -> Vec3<T> {
+        Vec3::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+}
+
+macro_rules! impl_signed {
+    ($($t:ty),*) => {
+        $(
+            impl_vec_signed!(Vec3<$t>);
+
+            impl Vec3<$t> {
+                /** The 6 points sharing a face with this one */
+                pub fn neighbours6(&self) -> impl Iterator<Item = Self> {
+                    let p = *self;
+                    [
+                        Vec3::new(-1, 0, 0),
+                        Vec3::new(1, 0, 0),
+                        Vec3::new(0, -1, 0),
+                        Vec3::new(0, 1, 0),
+                        Vec3::new(0, 0, -1),
+                        Vec3::new(0, 0, 1),
+                    ]
+                    .into_iter()
+                    .map(move |d| p + d)
+                }
+
+                /** The 26 points sharing a face, edge or corner with this one */
+                pub fn neighbours26(&self) -> impl Iterator<Item = Self> {
+                    let p = *self;
+                    (-1..=1)
+                        .flat_map(|x| (-1..=1).flat_map(move |y| (-1..=1).map(move |z| (x, y, z))))
+                        .filter(|d| *d != (0, 0, 0))
+                        .map(move |(x, y, z)| p + Vec3::new(x, y, z))
+                }
+            }
+        )*
+    };
+}
+
+impl_signed!(i8, i16, i32, i64, i128, isize);
+
+pub fn manhattan_distance3<T>(a: Vec3<T>, b: Vec3<T>) -> T
+where
+    T: Sub<Output = T> + Add<Output = T> + Ord + Copy,
+{
+    let dist = |p: T, q: T| if p > q { p - q } else { q - p };
+    dist(a.x, b.x) + dist(a.y, b.y) + dist(a.z, b.z)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Vec3::new(1i64, -2, 3);
+        let b = Vec3::new(4i64, 5, -6);
+        assert_eq!(a + b, Vec3::new(5, 3, -3));
+        assert_eq!(a.dot(b), -24);
+        assert_eq!(a.cross(b), Vec3::new(-3, 18, 13));
+        assert_eq!(a.cross(b).dot(a), 0);
+        assert_eq!((a - b).manhattan(), 19);
+        assert_eq!(manhattan_distance3(a, b), 19);
+        assert_eq!(a.rem_euclid(Vec3::new(2, 3, 4)), Vec3::new(1, 1, 3));
+        assert_eq!(-a * 2, Vec3::new(-2, 4, -6));
+        assert_eq!(vec![a, b].into_iter().sum::<Vec3<i64>>(), a + b);
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(Vec3::new(4i64, -6, 10).normalize(), Vec3::new(2, -3, 5));
+        assert_eq!(Vec3::new(0i64, 0, 0).normalize(), Vec3::new(0, 0, 0));
+        assert_eq!(Vec3::new(0i64, 0, -7).normalize(), Vec3::new(0, 0, -1));
+        assert_eq!(
+            Vec3::new(i8::MIN, 0, i8::MIN).normalize(),
+            Vec3::new(-1, 0, -1)
+        );
+        assert_eq!(
+            Vec3::new(i8::MIN, 64, 1).normalize(),
+            Vec3::new(i8::MIN, 64, 1)
+        );
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let p = Vec3::new(0i32, 0, 0);
+        assert_eq!(p.neighbours6().count(), 6);
+        assert!(p.neighbours6().all(|n| n.manhattan() == 1));
+        assert_eq!(p.neighbours26().count(), 26);
+        assert!(p.neighbours26().all(|n| n.chebyshev() == 1));
+    }
+}
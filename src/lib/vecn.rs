@@ -0,0 +1,164 @@
+use std::array;
+use std::ops::{Add, Index, IndexMut, Mul};
+
+use super::vec2::{impl_vec_ops, impl_vec_signed};
+
+/**
+* A vector with a compile-time number of components, for puzzles in 4 or more dimensions.
+* Has the same operators as `Vec2` and `Vec3`, applied component by component.
+*/
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct VecN<T, const N: usize>(pub [T; N]);
+
+impl<T, const N: usize> VecN<T, N> {
+    pub fn new(components: [T; N]) -> Self {
+        VecN(components)
+    }
+}
+
+impl<T: Default, const N: usize> Default for VecN<T, N> {
+    fn default() -> Self {
+        VecN(array::from_fn(|_| T::default()))
+    }
+}
+
+impl<T, const N: usize> Index<usize> for VecN<T, N> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.0[index]
+    }
+}
+
+impl<T, const N: usize> IndexMut<usize> for VecN<T, N> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.0[index]
+    }
+}
+
+impl<T, const N: usize> VecN<T, N> {
+    fn zip_with(self, other: Self, mut op: impl FnMut(T, T) -> T) -> Self {
+        let mut other = other.0.into_iter();
+        VecN(self.0.map(|a| op(a, other.next().unwrap())))
+    }
+
+    fn map(self, op: impl FnMut(T) -> T) -> Self {
+        VecN(self.0.map(op))
+    }
+
+    fn zip_assign(&mut self, other: Self, mut op: impl FnMut(&mut T, T)) {
+        for (a, b) in self.0.iter_mut().zip(other.0) {
+            op(a, b);
+        }
+    }
+
+    fn map_assign(&mut self, op: impl FnMut(&mut T)) {
+        self.0.iter_mut().for_each(op);
+    }
+
+    fn fold<A>(self, init: A, op: impl FnMut(A, T) -> A) -> A {
+        self.0.into_iter().fold(init, op)
+    }
+}
+
+impl_vec_ops!(VecN<T, const N: usize>);
+
+impl<T, const N: usize> VecN<T, N>
+where
+    T: Mul<Output = T> + Add<Output = T> + Default + Copy,
+{
+    /** Multiplies component by component */
+    pub fn component_mul(&self, other: VecN<T, N>) -> VecN<T, N> {
+        self.zip_with(other, |a, b| a * b)
+    }
+
+    pub fn dot(&self, other: VecN<T, N>) -> T {
+        self.component_mul(other)
+            .0
+            .into_iter()
+            .fold(T::default(), |acc, v| acc + v)
+    }
+}
+
+macro_rules! impl_signed {
+    ($($t:ty),*) => {
+        $(
+            impl_vec_signed!(VecN<$t, const N: usize>);
+
+            impl<const N: usize> VecN<$t, N> {
+                /** The 2N points one step away along a single axis */
+                pub fn orthogonal_neighbours(&self) -> impl Iterator<Item = Self> {
+                    let p = *self;
+                    (0..N).flat_map(move |axis| {
+                        [-1, 1].into_iter().map(move |step| {
+                            let mut n = p;
+                            n.0[axis] += step;
+                            n
+                        })
+                    })
+                }
+
+                /** The 3^N - 1 points differing by at most one in every component */
+                pub fn neighbours(&self) -> impl Iterator<Item = Self> {
+                    let p = *self;
+                    (0..3usize.pow(N as u32))
+                        .map(move |code| {
+                            let mut n = p;
+                            for (axis, c) in n.0.iter_mut().enumerate() {
+                                *c += (code / 3usize.pow(axis as u32) % 3) as $t - 1;
+                            }
+                            n
+                        })
+                        .filter(move |n| *n != p)
+                }
+            }
+        )*
+    };
+}
+
+impl_signed!(i8, i16, i32, i64, i128, isize);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let a = VecN::new([1i64, -2, 3, 4]);
+        let b = VecN::new([0i64, 5, -6, 1]);
+        assert_eq!(a + b, VecN::new([1, 3, -3, 5]));
+        assert_eq!(a - b, VecN::new([1, -7, 9, 3]));
+        assert_eq!(-a * 2, VecN::new([-2, 4, -6, -8]));
+        assert_eq!(a.dot(b), -24);
+        assert_eq!((a - b).manhattan(), 20);
+        assert_eq!((a - b).chebyshev(), 9);
+        assert_eq!(vec![a, b].into_iter().sum::<VecN<i64, 4>>(), a + b);
+        let mut c = a;
+        c -= b;
+        c *= 3;
+        assert_eq!(c % 4, VecN::new([3, -1, 3, 1]));
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(
+            VecN::new([4i64, -6, 10, 0]).normalize(),
+            VecN::new([2, -3, 5, 0])
+        );
+        assert_eq!(VecN::new([0i64; 4]).normalize(), VecN::new([0; 4]));
+        assert_eq!(VecN::new([i8::MIN; 4]).normalize(), VecN::new([-1; 4]));
+        assert_eq!(
+            VecN::new([i8::MIN, 64, 0, 1]).normalize(),
+            VecN::new([i8::MIN, 64, 0, 1])
+        );
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let p = VecN::new([0i32; 4]);
+        assert_eq!(p.orthogonal_neighbours().count(), 8);
+        assert!(p.orthogonal_neighbours().all(|n| n.manhattan() == 1));
+        assert_eq!(p.neighbours().count(), 80);
+        assert!(p.neighbours().all(|n| n.chebyshev() == 1));
+    }
+}